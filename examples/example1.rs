@@ -13,7 +13,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     let min_turn_radius = 10.0;
     let pitch_lims = (PI * -15.0 / 180.0, PI * 20.0 / 180.0);
 
-    let dubins = DubinsManeuver3D::new(qi, qf, min_turn_radius, pitch_lims);
+    let dubins = DubinsManeuver3D::try_new(qi, qf, min_turn_radius, pitch_lims)?;
     let samples = dubins.compute_sampling(500);
     println!("{}", samples.len());
 
@@ -0,0 +1,179 @@
+use crate::{State, mod_pi};
+use crate::dubins3d::DubinsManeuver3D;
+use crate::timing::TimedState;
+use crate::vehicle::VehicleConstraints;
+
+/// State along a maneuver together with the roll of a coordinated turn
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BankedState {
+    pub state: State,
+    pub roll: f64
+}
+
+/// Bank angle of a coordinated turn, positive to the left
+///
+/// `yaw_rate` and `pitch_rate` are rates in time. The bank tilts the lift
+/// vector so that it provides both the centripetal acceleration of the turn
+/// and the normal acceleration of the vertical arc against gravity, it
+/// exceeds pi/2 in magnitude where the vertical arc needs a negative load.
+pub fn bank_angle(airspeed: f64, yaw_rate: f64, pitch: f64, pitch_rate: f64, gravity: f64) -> f64 {
+    let lateral = airspeed * yaw_rate * pitch.cos();
+    let normal = airspeed * pitch_rate + gravity * pitch.cos();
+    return lateral.atan2(normal);
+}
+
+/// Radius of a level coordinated turn flown at the given bank angle
+pub fn bank_limited_radius(airspeed: f64, max_bank: f64, gravity: f64) -> f64 {
+    return airspeed * airspeed / (gravity * max_bank.tan());
+}
+
+impl TimedState {
+    /// Bank angle of a coordinated turn through this state
+    pub fn bank_angle(&self, gravity: f64) -> f64 {
+        return bank_angle(self.speed, self.yaw_rate, self.state.pitch, self.pitch_rate, gravity);
+    }
+}
+
+impl VehicleConstraints {
+    /// Bank angle of a level coordinated turn at the maximum load factor
+    pub fn max_bank_angle(&self) -> f64 {
+        return (1.0 / self.max_load_factor).acos();
+    }
+
+    /// Limit the load factor to the one of a level coordinated turn at
+    /// `max_bank`, which also limits `rhomin` to `bank_limited_radius`
+    pub fn with_max_bank_angle(mut self, max_bank: f64) -> Self {
+        self.max_load_factor = 1.0 / max_bank.cos();
+        return self;
+    }
+}
+
+impl DubinsManeuver3D {
+    /// Bank angle of a coordinated turn at arc length `s` flown at constant
+    /// airspeed
+    ///
+    /// Panics if the maneuver holds no path.
+    pub fn bank_angle_at(&self, s: f64, airspeed: f64, gravity: f64) -> f64 {
+        let pitch = self.state_at(s).pitch;
+        let yaw_rate = self.yaw_rate_at(s) * airspeed;
+        let pitch_rate = self.pitch_rate_at(s) * airspeed;
+        return bank_angle(airspeed, yaw_rate, pitch, pitch_rate, gravity);
+    }
+
+    /// States spaced `step` apart in arc length with the bank angle flown at
+    /// constant airspeed, the last item is exactly the final state
+    ///
+    /// Panics if `step` is not positive or the maneuver holds no path.
+    pub fn compute_sampling_banked(&self, step: f64, airspeed: f64, gravity: f64) -> Vec<BankedState> {
+        let count = self.samples(step).len();
+        return self.samples(step).enumerate().map(|(i, state)| {
+            let s = if i + 1 == count { self.length() } else { step * i as f64 };
+            return BankedState{state: state, roll: self.bank_angle_at(s, airspeed, gravity)};
+        }).collect();
+    }
+
+    /// Largest bank angle magnitude along the maneuver flown at constant
+    /// airspeed
+    ///
+    /// The maneuver is split where a lateral or longitudinal segment ends.
+    /// Within a piece both curvatures are constant and the pitch changes
+    /// linearly, so the bank angle only peaks at the ends of the piece, at
+    /// level flight or where the vertical arc balances gravity. Panics if the
+    /// maneuver holds no path.
+    pub fn max_bank_angle(&self, airspeed: f64, gravity: f64) -> f64 {
+        let breaks = self.piece_breaks();
+
+        let mut max_bank: f64 = 0.0;
+        for piece in breaks.windows(2) {
+            let (s0, s1) = (piece[0], piece[1]);
+            let mid = 0.5 * (s0 + s1);
+            let lateral_curvature = self.yaw_rate_at(mid) / self.state_at(mid).pitch.cos();
+            let pitch_rate = self.pitch_rate_at(mid);
+            let pitch0 = mod_pi(self.state_at(s0).pitch);
+            let pitch1 = pitch0 + pitch_rate * (s1 - s0);
+
+            let mut candidates = vec![pitch0, pitch1, 0.0];
+            let balance = -2.0 * airspeed * airspeed * pitch_rate / gravity;
+            if balance.abs() <= 1.0 {
+                candidates.push(balance.acos());
+                candidates.push(-balance.acos());
+            }
+            for pitch in candidates {
+                if pitch < pitch0.min(pitch1) || pitch > pitch0.max(pitch1) {
+                    continue;
+                }
+                let yaw_rate = lateral_curvature * pitch.cos() * airspeed;
+                let bank = bank_angle(airspeed, yaw_rate, pitch, pitch_rate * airspeed, gravity);
+                max_bank = max_bank.max(bank.abs());
+            }
+        }
+        return max_bank;
+    }
+
+    /// Whether the maneuver can be flown at constant airspeed without
+    /// banking beyond `max_bank`
+    pub fn within_bank_limit(&self, max_bank: f64, airspeed: f64, gravity: f64) -> bool {
+        return self.max_bank_angle(airspeed, gravity) <= max_bank;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    const AIRSPEED: f64 = 20.0;
+    const GRAVITY: f64 = 9.81;
+
+    #[test]
+    fn level_turn_banks_to_the_coordinated_angle() {
+        let qi = State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+        let left = DubinsManeuver3D::new(qi, State{x: 0.0, y: 80.0, z: 0.0, yaw: PI, pitch: 0.0}, 40.0, (-0.3, 0.3));
+        let right = DubinsManeuver3D::new(qi, State{x: 0.0, y: -80.0, z: 0.0, yaw: PI, pitch: 0.0}, 40.0, (-0.3, 0.3));
+        let radius = left.lateral().unwrap().rhomin();
+        let expected = (AIRSPEED * AIRSPEED / (GRAVITY * radius)).atan();
+        let s = 0.5 * left.length();
+        assert!((left.bank_angle_at(s, AIRSPEED, GRAVITY) - expected).abs() < 1e-9);
+        assert!((right.bank_angle_at(s, AIRSPEED, GRAVITY) + expected).abs() < 1e-9);
+        assert!((bank_limited_radius(AIRSPEED, expected, GRAVITY) - radius).abs() < 1e-9);
+        // The longitudinal solution keeps vanishing vertical arcs at both ends
+        assert!((left.max_bank_angle(AIRSPEED, GRAVITY) - expected).abs() < 1e-3);
+    }
+
+    #[test]
+    fn wings_level_on_straights() {
+        let qi = State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+        let level = DubinsManeuver3D::new(qi, State{x: 400.0, ..qi}, 40.0, (-0.3, 0.3));
+        let climb = State{pitch: 0.2, ..qi};
+        let goal = State{x: 400.0 * 0.2f64.cos(), z: 400.0 * 0.2f64.sin(), ..climb};
+        let climbing = DubinsManeuver3D::new(climb, goal, 40.0, (-0.3, 0.3));
+        for maneuver in [level, climbing] {
+            for sample in maneuver.compute_sampling_banked(10.0, AIRSPEED, GRAVITY) {
+                assert!(sample.roll.abs() < 1e-9);
+            }
+            assert!(maneuver.max_bank_angle(AIRSPEED, GRAVITY) < 1e-9);
+        }
+    }
+
+    #[test]
+    fn max_bank_angle_bounds_the_samples() {
+        let qi = State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+        let goals = [
+            State{x: 120.0, y: 90.0, z: 60.0, yaw: 2.5, pitch: -0.1},
+            State{x: -50.0, y: 30.0, z: -80.0, yaw: -1.0, pitch: 0.2},
+            State{x: 20.0, y: -150.0, z: 200.0, yaw: 0.5, pitch: 0.0}
+        ];
+        for qf in goals {
+            let maneuver = DubinsManeuver3D::new(qi, qf, 40.0, (-0.4, 0.4));
+            let max_bank = maneuver.max_bank_angle(AIRSPEED, GRAVITY);
+            let sampled = maneuver.compute_sampling_banked(0.1, AIRSPEED, GRAVITY)
+                .iter().fold(0.0f64, |max, sample| max.max(sample.roll.abs()));
+            assert!(sampled <= max_bank + 1e-9, "{:?}: {} > {}", qf, sampled, max_bank);
+            assert!(sampled >= max_bank - 1e-3, "{:?}: {} < {}", qf, sampled, max_bank);
+
+            assert!(maneuver.within_bank_limit(max_bank + 1e-9, AIRSPEED, GRAVITY));
+            assert!(!maneuver.within_bank_limit(max_bank - 1e-3, AIRSPEED, GRAVITY));
+        }
+    }
+}
@@ -0,0 +1,684 @@
+use crate::State;
+use crate::frame::Frame;
+use crate::dubins3d::DubinsManeuver3D;
+use crate::path::DubinsPath3D;
+use crate::rrt::CollisionChecker;
+
+/// Distance below which a maneuver is considered to touch a shape
+const CONTACT_TOLERANCE: f64 = 1e-6;
+
+/// Point and unit tangent of a curve at an arc length
+pub type CurvePoint = ((f64, f64, f64), (f64, f64, f64));
+
+/// Solid region of space a maneuver must not enter
+pub trait Shape {
+    /// Signed distance from `p` to the surface, negative inside
+    ///
+    /// Must never exceed the true distance, a smaller value only slows down
+    /// the tracing of curved pieces.
+    fn distance(&self, p: (f64, f64, f64)) -> f64;
+
+    /// Smallest `t` in [0, length] at which `origin + t * direction` lies in
+    /// the shape, `direction` has unit length
+    ///
+    /// By default the segment is traced with `distance`.
+    fn segment_contact(&self, origin: (f64, f64, f64), direction: (f64, f64, f64), length: f64) -> Option<f64> {
+        return trace(self, |t| add(origin, scale(direction, t)), 0.0, length);
+    }
+
+    /// Smallest arc length in [s0, s1] at which `curve` lies in the shape,
+    /// the curve bends by at most `curvature`
+    ///
+    /// By default the curve is traced with `distance`, and close to the
+    /// surface it is followed by chords tested with `segment_contact` that
+    /// stay within `tolerance` of the curve, so only overlaps shallower than
+    /// `tolerance` may be missed.
+    fn curve_contact(&self, curve: &dyn Fn(f64) -> CurvePoint, s0: f64, s1: f64, curvature: f64, tolerance: f64) -> Option<f64> {
+        return trace_curve(self, curve, s0, s1, curvature, tolerance);
+    }
+}
+
+/// Axis-aligned box
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Aabb {
+    pub min: (f64, f64, f64),
+    pub max: (f64, f64, f64)
+}
+
+impl Aabb {
+    pub fn new(min: (f64, f64, f64), max: (f64, f64, f64)) -> Self {
+        return Aabb{min: min, max: max};
+    }
+}
+
+impl Shape for Aabb {
+    fn distance(&self, p: (f64, f64, f64)) -> f64 {
+        let q = (
+            (self.min.0 - p.0).max(p.0 - self.max.0),
+            (self.min.1 - p.1).max(p.1 - self.max.1),
+            (self.min.2 - p.2).max(p.2 - self.max.2)
+        );
+        let outside = (q.0.max(0.0).powi(2) + q.1.max(0.0).powi(2) + q.2.max(0.0).powi(2)).sqrt();
+        let inside = q.0.max(q.1).max(q.2).min(0.0);
+        return outside + inside;
+    }
+
+    fn segment_contact(&self, origin: (f64, f64, f64), direction: (f64, f64, f64), length: f64) -> Option<f64> {
+        let mut interval = Some((0.0, length));
+        interval = slab(origin.0, direction.0, self.min.0, self.max.0, interval);
+        interval = slab(origin.1, direction.1, self.min.1, self.max.1, interval);
+        interval = slab(origin.2, direction.2, self.min.2, self.max.2, interval);
+        return interval.map(|(t, _)| t);
+    }
+}
+
+/// Ball around a center
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sphere {
+    pub center: (f64, f64, f64),
+    pub radius: f64
+}
+
+impl Sphere {
+    pub fn new(center: (f64, f64, f64), radius: f64) -> Self {
+        return Sphere{center: center, radius: radius};
+    }
+}
+
+impl Shape for Sphere {
+    fn distance(&self, p: (f64, f64, f64)) -> f64 {
+        return norm(sub(p, self.center)) - self.radius;
+    }
+
+    fn segment_contact(&self, origin: (f64, f64, f64), direction: (f64, f64, f64), length: f64) -> Option<f64> {
+        let m = sub(origin, self.center);
+        let c = dot(m, m) - self.radius * self.radius;
+        if c <= 0.0 {
+            return Some(0.0);
+        }
+        let b = dot(m, direction);
+        let discriminant = b * b - c;
+        if b > 0.0 || discriminant < 0.0 {
+            return None;
+        }
+        let t = -b - discriminant.sqrt();
+        return if t <= length { Some(t) } else { None };
+    }
+}
+
+/// Upright circular cylinder between two altitudes, such as a no-fly zone
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VerticalCylinder {
+    pub center: (f64, f64),
+    pub radius: f64,
+    pub z_min: f64,
+    pub z_max: f64
+}
+
+impl VerticalCylinder {
+    pub fn new(center: (f64, f64), radius: f64, z_min: f64, z_max: f64) -> Self {
+        return VerticalCylinder{center: center, radius: radius, z_min: z_min, z_max: z_max};
+    }
+}
+
+impl Shape for VerticalCylinder {
+    fn distance(&self, p: (f64, f64, f64)) -> f64 {
+        let radial = (p.0 - self.center.0).hypot(p.1 - self.center.1) - self.radius;
+        let vertical = (self.z_min - p.2).max(p.2 - self.z_max);
+        return combine_distances(radial, vertical);
+    }
+
+    fn segment_contact(&self, origin: (f64, f64, f64), direction: (f64, f64, f64), length: f64) -> Option<f64> {
+        let interval = slab(origin.2, direction.2, self.z_min, self.z_max, Some((0.0, length)));
+        let m = (origin.0 - self.center.0, origin.1 - self.center.1);
+        let radial = circle_interval(m, (direction.0, direction.1), self.radius);
+        return intersect(interval, radial).map(|(t, _)| t);
+    }
+}
+
+/// Everything on the far side of a plane from its normal, such as terrain
+/// below a floor or airspace above a ceiling
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HalfSpace {
+    /// Unit normal pointing out of the solid region
+    pub normal: (f64, f64, f64),
+    pub offset: f64
+}
+
+impl HalfSpace {
+    /// Points `p` with `dot(normal, p) < offset`, the normal is normalized
+    pub fn new(normal: (f64, f64, f64), offset: f64) -> Self {
+        let length = norm(normal);
+        return HalfSpace{normal: scale(normal, 1.0 / length), offset: offset / length};
+    }
+
+    /// Everything below altitude `z`
+    pub fn floor(z: f64) -> Self {
+        return HalfSpace{normal: (0.0, 0.0, 1.0), offset: z};
+    }
+
+    /// Everything above altitude `z`
+    pub fn ceiling(z: f64) -> Self {
+        return HalfSpace{normal: (0.0, 0.0, -1.0), offset: -z};
+    }
+}
+
+impl Shape for HalfSpace {
+    fn distance(&self, p: (f64, f64, f64)) -> f64 {
+        return dot(self.normal, p) - self.offset;
+    }
+
+    fn segment_contact(&self, origin: (f64, f64, f64), direction: (f64, f64, f64), length: f64) -> Option<f64> {
+        let d = self.distance(origin);
+        if d <= 0.0 {
+            return Some(0.0);
+        }
+        let rate = dot(self.normal, direction);
+        if rate >= 0.0 {
+            return None;
+        }
+        let t = -d / rate;
+        return if t <= length { Some(t) } else { None };
+    }
+
+    /// The distance is linear, so along the curve it falls no faster than
+    /// its slope plus the curvature allows, and each step goes to where it
+    /// could first reach zero, so the contact is found exactly whatever the
+    /// tolerance
+    fn curve_contact(&self, curve: &dyn Fn(f64) -> CurvePoint, s0: f64, s1: f64, curvature: f64, _tolerance: f64) -> Option<f64> {
+        let mut s = s0;
+        loop {
+            let (p, tangent) = curve(s);
+            let d = self.distance(p);
+            if d <= CONTACT_TOLERANCE {
+                return Some(s);
+            }
+            if s >= s1 {
+                return None;
+            }
+            let rate = dot(self.normal, tangent);
+            let step = if curvature > 0.0 {
+                (rate + (rate * rate + 2.0 * curvature * d).sqrt()) / curvature
+            }
+            else if rate < 0.0 {
+                -d / rate
+            }
+            else {
+                return None;
+            };
+            s = (s + step).min(s1);
+        }
+    }
+}
+
+/// Simple polygon in the x-y plane extruded between two altitudes
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PolygonalPrism {
+    pub vertices: Vec<(f64, f64)>,
+    pub z_min: f64,
+    pub z_max: f64
+}
+
+impl PolygonalPrism {
+    pub fn new(vertices: Vec<(f64, f64)>, z_min: f64, z_max: f64) -> Self {
+        return PolygonalPrism{vertices: vertices, z_min: z_min, z_max: z_max};
+    }
+
+    fn edges(&self) -> impl Iterator<Item = ((f64, f64), (f64, f64))> + '_ {
+        let n = self.vertices.len();
+        return (0..n).map(move |i| (self.vertices[i], self.vertices[(i + 1) % n]));
+    }
+
+    /// Even-odd rule
+    fn contains(&self, p: (f64, f64)) -> bool {
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            if (a.1 > p.1) != (b.1 > p.1) && p.0 < a.0 + (p.1 - a.1) / (b.1 - a.1) * (b.0 - a.0) {
+                inside = !inside;
+            }
+        }
+        return inside;
+    }
+}
+
+impl Shape for PolygonalPrism {
+    fn distance(&self, p: (f64, f64, f64)) -> f64 {
+        let mut squared = core::f64::INFINITY;
+        for (a, b) in self.edges() {
+            let e = (b.0 - a.0, b.1 - a.1);
+            let w = (p.0 - a.0, p.1 - a.1);
+            let u = ((w.0 * e.0 + w.1 * e.1) / (e.0 * e.0 + e.1 * e.1)).clamp(0.0, 1.0);
+            squared = squared.min((w.0 - u * e.0).powi(2) + (w.1 - u * e.1).powi(2));
+        }
+        let planar = if self.contains((p.0, p.1)) { -squared.sqrt() } else { squared.sqrt() };
+        let vertical = (self.z_min - p.2).max(p.2 - self.z_max);
+        return combine_distances(planar, vertical);
+    }
+
+    fn segment_contact(&self, origin: (f64, f64, f64), direction: (f64, f64, f64), length: f64) -> Option<f64> {
+        let (lo, hi) = slab(origin.2, direction.2, self.z_min, self.z_max, Some((0.0, length)))?;
+
+        // Parameters where the projected segment crosses the outline, each
+        // crossing toggles between outside and inside
+        let mut crossings: Vec<f64> = Vec::new();
+        for (a, b) in self.edges() {
+            let e = (b.0 - a.0, b.1 - a.1);
+            let denominator = direction.0 * e.1 - direction.1 * e.0;
+            if denominator == 0.0 {
+                continue;
+            }
+            let w = (a.0 - origin.0, a.1 - origin.1);
+            let t = (w.0 * e.1 - w.1 * e.0) / denominator;
+            let u = (w.0 * direction.1 - w.1 * direction.0) / denominator;
+            if (0.0..1.0).contains(&u) && t > 0.0 && t <= hi {
+                crossings.push(t);
+            }
+        }
+        crossings.sort_by(|a, b| a.total_cmp(b));
+
+        let mut inside = self.contains((origin.0, origin.1));
+        let mut entered: f64 = 0.0;
+        for t in crossings.into_iter().chain(core::iter::once(core::f64::INFINITY)) {
+            if inside && t >= lo {
+                return Some(entered.max(lo));
+            }
+            inside = !inside;
+            entered = t;
+        }
+        return None;
+    }
+}
+
+/// Union of shapes, also usable as the `CollisionChecker` of a planner
+#[derive(Default)]
+pub struct Obstacles {
+    shapes: Vec<Box<dyn Shape>>
+}
+
+impl Obstacles {
+    pub fn new() -> Self {
+        return Obstacles{shapes: Vec::new()};
+    }
+
+    /// Add a shape to the union
+    pub fn push<S: Shape + 'static>(&mut self, shape: S) {
+        self.shapes.push(Box::new(shape));
+    }
+
+    /// Number of shapes in the union
+    pub fn len(&self) -> usize {
+        return self.shapes.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.shapes.is_empty();
+    }
+}
+
+impl Shape for Obstacles {
+    fn distance(&self, p: (f64, f64, f64)) -> f64 {
+        return self.shapes.iter().map(|shape| shape.distance(p)).fold(core::f64::INFINITY, f64::min);
+    }
+
+    fn segment_contact(&self, origin: (f64, f64, f64), direction: (f64, f64, f64), length: f64) -> Option<f64> {
+        return self.shapes.iter()
+            .filter_map(|shape| shape.segment_contact(origin, direction, length))
+            .min_by(|a, b| a.total_cmp(b));
+    }
+
+    fn curve_contact(&self, curve: &dyn Fn(f64) -> CurvePoint, s0: f64, s1: f64, curvature: f64, tolerance: f64) -> Option<f64> {
+        return self.shapes.iter()
+            .filter_map(|shape| shape.curve_contact(curve, s0, s1, curvature, tolerance))
+            .min_by(|a, b| a.total_cmp(b));
+    }
+}
+
+impl CollisionChecker for Obstacles {
+    fn is_state_free(&self, state: &State) -> bool {
+        return self.distance((state.x, state.y, state.z)) > CONTACT_TOLERANCE;
+    }
+
+    /// Curved pieces are followed by chords within `resolution` of the
+    /// maneuver, so only overlaps shallower than `resolution` may be missed
+    fn is_maneuver_free(&self, maneuver: &DubinsManeuver3D, resolution: f64) -> bool {
+        return maneuver.first_contact_within(self, resolution).is_none();
+    }
+}
+
+impl DubinsManeuver3D {
+    /// First arc length at which the maneuver touches `shape`, `None` if it
+    /// stays clear
+    ///
+    /// Straight pieces are tested with `Shape::segment_contact` and curved
+    /// pieces with `Shape::curve_contact`. Panics if the maneuver holds no
+    /// path.
+    pub fn first_contact<S: Shape + ?Sized>(&self, shape: &S) -> Option<f64> {
+        return self.first_contact_within(shape, CONTACT_TOLERANCE);
+    }
+
+    /// First arc length at which the maneuver touches `shape`, where curved
+    /// pieces may be followed by chords up to `tolerance` away from them
+    ///
+    /// The curvature bound of a piece comes from its own lateral and vertical
+    /// radii, which are smaller than `rhomin` for the spirals of a lower bound.
+    /// Panics if the maneuver holds no path.
+    pub fn first_contact_within<S: Shape + ?Sized>(&self, shape: &S, tolerance: f64) -> Option<f64> {
+        let curve = |s: f64| {
+            let q = self.state_at(s);
+            return ((q.x, q.y, q.z), q.direction(Frame::ENU));
+        };
+        for piece in self.piece_breaks().windows(2) {
+            let (s0, s1) = (piece[0], piece[1]);
+            let mid = 0.5 * (s0 + s1);
+            let curvature = self.piece_curvature(mid);
+            let contact = if curvature == 0.0 {
+                let q = self.state_at(s0);
+                shape.segment_contact((q.x, q.y, q.z), q.direction(Frame::ENU), s1 - s0).map(|t| s0 + t)
+            }
+            else {
+                shape.curve_contact(&curve, s0, s1, curvature, tolerance)
+            };
+            if contact.is_some() {
+                return contact;
+            }
+        }
+        return None;
+    }
+
+    /// Bound on the curvature over the piece holding `s`
+    ///
+    /// The lateral curvature is the yaw rate over the cosine of the pitch and
+    /// is constant within a piece, while the true curvature only shrinks it by
+    /// another cosine squared.
+    fn piece_curvature(&self, s: f64) -> f64 {
+        let lateral_curvature = self.yaw_rate_at(s) / self.state_at(s).pitch.cos();
+        return self.pitch_rate_at(s).hypot(lateral_curvature);
+    }
+
+    /// Whether the maneuver touches `shape` anywhere
+    pub fn intersects<S: Shape + ?Sized>(&self, shape: &S) -> bool {
+        return self.first_contact(shape).is_some();
+    }
+}
+
+impl DubinsPath3D {
+    /// First arc length along the whole path at which it touches `shape`
+    pub fn first_contact<S: Shape + ?Sized>(&self, shape: &S) -> Option<f64> {
+        let mut offset = 0.0;
+        for leg in self.legs() {
+            if let Some(s) = leg.first_contact(shape) {
+                return Some(offset + s);
+            }
+            offset += leg.length();
+        }
+        return None;
+    }
+}
+
+/// Advance along a curve bending by at most `curvature` from `s0` to `s1` by
+/// the distance to the shape until it is touched
+///
+/// The curve moves no further than its arc length, so a step by the distance
+/// cannot skip a contact. Closer to the surface than a chord length whose
+/// sagitta reaches `tolerance`, the curve is followed by chords of that length
+/// instead, so that grazing the surface does not shrink the steps to nothing.
+fn trace_curve<S: Shape + ?Sized>(shape: &S, curve: &dyn Fn(f64) -> CurvePoint, s0: f64, s1: f64, curvature: f64, tolerance: f64) -> Option<f64> {
+    let chord_length = if curvature > 0.0 { (8.0 * tolerance.max(CONTACT_TOLERANCE) / curvature).sqrt() } else { s1 - s0 };
+    let mut s = s0;
+    loop {
+        let p = curve(s).0;
+        let d = shape.distance(p);
+        if d <= CONTACT_TOLERANCE {
+            return Some(s);
+        }
+        if s >= s1 {
+            return None;
+        }
+        if d >= chord_length {
+            s = (s + d).min(s1);
+            continue;
+        }
+
+        let next = (s + chord_length).min(s1);
+        let chord = sub(curve(next).0, p);
+        let length = norm(chord);
+        if length > 0.0 {
+            if let Some(t) = shape.segment_contact(p, scale(chord, 1.0 / length), length) {
+                return Some(s + (next - s) * t / length);
+            }
+        }
+        s = next;
+    }
+}
+
+/// Advance along a straight line parametrized by length from `s0` to `s1` by
+/// the distance to the shape until it is touched
+fn trace<S: Shape + ?Sized, F: Fn(f64) -> (f64, f64, f64)>(shape: &S, position: F, s0: f64, s1: f64) -> Option<f64> {
+    let mut s = s0;
+    loop {
+        let d = shape.distance(position(s));
+        if d <= CONTACT_TOLERANCE {
+            return Some(s);
+        }
+        if s >= s1 {
+            return None;
+        }
+        s = (s + d).min(s1);
+    }
+}
+
+/// Restrict a parameter interval to where `p + t * d` lies between `min` and `max`
+fn slab(p: f64, d: f64, min: f64, max: f64, interval: Option<(f64, f64)>) -> Option<(f64, f64)> {
+    let (lo, hi) = interval?;
+    if d == 0.0 {
+        return if p >= min && p <= max { Some((lo, hi)) } else { None };
+    }
+    let t1 = (min - p) / d;
+    let t2 = (max - p) / d;
+    let lo = lo.max(t1.min(t2));
+    let hi = hi.min(t1.max(t2));
+    return if lo <= hi { Some((lo, hi)) } else { None };
+}
+
+/// Parameter interval where `m + t * d` lies within a circle around the origin
+fn circle_interval(m: (f64, f64), d: (f64, f64), radius: f64) -> Option<(f64, f64)> {
+    let a = d.0 * d.0 + d.1 * d.1;
+    let c = m.0 * m.0 + m.1 * m.1 - radius * radius;
+    if a == 0.0 {
+        return if c <= 0.0 { Some((core::f64::NEG_INFINITY, core::f64::INFINITY)) } else { None };
+    }
+    let b = m.0 * d.0 + m.1 * d.1;
+    let discriminant = b * b - a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    return Some(((-b - root) / a, (-b + root) / a));
+}
+
+fn intersect(a: Option<(f64, f64)>, b: Option<(f64, f64)>) -> Option<(f64, f64)> {
+    let (a, b) = (a?, b?);
+    let lo = a.0.max(b.0);
+    let hi = a.1.min(b.1);
+    return if lo <= hi { Some((lo, hi)) } else { None };
+}
+
+/// Signed distance of the intersection of two extruded regions
+fn combine_distances(a: f64, b: f64) -> f64 {
+    if a > 0.0 && b > 0.0 {
+        return a.hypot(b);
+    }
+    return a.max(b);
+}
+
+fn add(a: (f64, f64, f64), b: (f64, f64, f64)) -> (f64, f64, f64) {
+    return (a.0 + b.0, a.1 + b.1, a.2 + b.2);
+}
+
+fn sub(a: (f64, f64, f64), b: (f64, f64, f64)) -> (f64, f64, f64) {
+    return (a.0 - b.0, a.1 - b.1, a.2 - b.2);
+}
+
+fn scale(a: (f64, f64, f64), k: f64) -> (f64, f64, f64) {
+    return (a.0 * k, a.1 * k, a.2 * k);
+}
+
+fn dot(a: (f64, f64, f64), b: (f64, f64, f64)) -> f64 {
+    return a.0 * b.0 + a.1 * b.1 + a.2 * b.2;
+}
+
+fn norm(a: (f64, f64, f64)) -> f64 {
+    return dot(a, a).sqrt();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PI;
+
+    fn u_turn(z: f64) -> DubinsManeuver3D {
+        let qi = State{x: 0.0, y: 0.0, z: z, yaw: 0.0, pitch: 0.0};
+        let qf = State{x: 0.0, y: 200.0, z: z, yaw: PI, pitch: 0.0};
+        return DubinsManeuver3D::new(qi, qf, 100.0, (-0.3, 0.3));
+    }
+
+    fn straight(z: f64) -> DubinsManeuver3D {
+        let qi = State{x: 0.0, y: 0.0, z: z, yaw: 0.0, pitch: 0.0};
+        let qf = State{x: 400.0, y: 0.0, z: z, yaw: 0.0, pitch: 0.0};
+        return DubinsManeuver3D::new(qi, qf, 100.0, (-0.3, 0.3));
+    }
+
+    /// Asserts that `shape` is first touched at `s` on its surface
+    fn assert_first_contact<S: Shape>(maneuver: &DubinsManeuver3D, shape: &S, s: f64) {
+        let q = maneuver.state_at(s);
+        assert!(shape.distance((q.x, q.y, q.z)).abs() < 1e-5);
+        for i in 0..1000 {
+            let q = maneuver.state_at(s * i as f64 / 1000.0);
+            assert!(shape.distance((q.x, q.y, q.z)) > 0.0);
+        }
+    }
+
+    /// Point of the maneuver farthest along x, where the curve runs along y
+    fn farthest_along_x(maneuver: &DubinsManeuver3D) -> (f64, f64, f64) {
+        let q = (0..=100000)
+            .map(|i| maneuver.state_at(maneuver.length() * i as f64 / 100000.0))
+            .max_by(|a, b| a.x.total_cmp(&b.x))
+            .unwrap();
+        return (q.x, q.y, q.z);
+    }
+
+    #[test]
+    fn box_contact_and_near_miss() {
+        let maneuver = straight(50.0);
+        assert_eq!(maneuver.first_contact(&Aabb::new((100.0, 1e-4, 0.0), (150.0, 10.0, 100.0))), None);
+        let aabb = Aabb::new((100.0, -1e-4, 0.0), (150.0, 10.0, 100.0));
+        let s = maneuver.first_contact(&aabb).unwrap();
+        assert!((s - 100.0).abs() < 1e-6);
+        assert_first_contact(&maneuver, &aabb, s);
+
+        let maneuver = u_turn(50.0);
+        let (x, y, z) = farthest_along_x(&maneuver);
+        assert_eq!(maneuver.first_contact(&Aabb::new((x + 1e-4, y - 50.0, z - 50.0), (x + 100.0, y + 50.0, z + 50.0))), None);
+        let aabb = Aabb::new((x - 1e-3, y - 50.0, z - 50.0), (x + 100.0, y + 50.0, z + 50.0));
+        let s = maneuver.first_contact(&aabb).unwrap();
+        assert!((maneuver.state_at(s).y - y).abs() < 1.0);
+        assert_first_contact(&maneuver, &aabb, s);
+    }
+
+    #[test]
+    fn sphere_contact_and_near_miss() {
+        let maneuver = straight(50.0);
+        assert_eq!(maneuver.first_contact(&Sphere::new((200.0, 10.0, 50.0), 10.0 - 1e-4)), None);
+        let sphere = Sphere::new((200.0, 10.0, 50.0), 10.0 + 1e-4);
+        let s = maneuver.first_contact(&sphere).unwrap();
+        assert!((s - 200.0).abs() < 0.1);
+        assert_first_contact(&maneuver, &sphere, s);
+
+        let maneuver = u_turn(50.0);
+        let (x, y, z) = farthest_along_x(&maneuver);
+        assert_eq!(maneuver.first_contact(&Sphere::new((x + 5.0, y, z), 5.0 - 1e-4)), None);
+        let sphere = Sphere::new((x + 5.0, y, z), 5.0 + 1e-3);
+        let s = maneuver.first_contact(&sphere).unwrap();
+        assert!((maneuver.state_at(s).y - y).abs() < 1.0);
+        assert_first_contact(&maneuver, &sphere, s);
+    }
+
+    #[test]
+    fn prism_contact_and_near_miss() {
+        let square = |x0: f64, y0: f64, x1: f64, y1: f64| {
+            return PolygonalPrism::new(vec![(x0, y0), (x1, y0), (x1, y1), (x0, y1)], 0.0, 100.0);
+        };
+        let maneuver = straight(50.0);
+        assert_eq!(maneuver.first_contact(&square(100.0, 1e-4, 150.0, 10.0)), None);
+        let prism = square(100.0, -1e-4, 150.0, 10.0);
+        let s = maneuver.first_contact(&prism).unwrap();
+        assert!((s - 100.0).abs() < 1e-6);
+        assert_first_contact(&maneuver, &prism, s);
+
+        let maneuver = u_turn(50.0);
+        let (x, y, _) = farthest_along_x(&maneuver);
+        assert_eq!(maneuver.first_contact(&square(x + 1e-4, y - 50.0, x + 100.0, y + 50.0)), None);
+        let prism = square(x - 1e-3, y - 50.0, x + 100.0, y + 50.0);
+        let s = maneuver.first_contact(&prism).unwrap();
+        assert!((maneuver.state_at(s).y - y).abs() < 1.0);
+        assert_first_contact(&maneuver, &prism, s);
+    }
+
+    #[test]
+    fn piece_curvature_bounds_lower_bound_spirals() {
+        let qi = State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+        let qf = State{x: 0.0, y: 60.0, z: 200.0, yaw: PI, pitch: 0.0};
+        let bound = DubinsManeuver3D::get_lower_bound(qi, qf, 40.0, (-0.5, 0.5)).unwrap();
+        let mut tightest: f64 = 0.0;
+        for piece in bound.piece_breaks().windows(2) {
+            let curvature = bound.piece_curvature(0.5 * (piece[0] + piece[1]));
+            tightest = tightest.max(curvature);
+            for i in 1..100 {
+                let s = piece[0] + (piece[1] - piece[0]) * i as f64 / 100.0;
+                assert!(bound.curvature_at(s) <= curvature * (1.0 + 1e-9));
+            }
+        }
+        assert!(tightest > 1.0 / 40.0);
+    }
+
+    #[test]
+    fn coarser_tolerance_still_finds_deep_contacts() {
+        let maneuver = u_turn(50.0);
+        let (x, y, _) = farthest_along_x(&maneuver);
+        let prism = PolygonalPrism::new(vec![(x - 1.0, y - 1.0), (x + 1.0, y - 1.0), (x + 1.0, y + 1.0), (x - 1.0, y + 1.0)], 0.0, 100.0);
+        let exact = maneuver.first_contact(&prism).unwrap();
+        let coarse = maneuver.first_contact_within(&prism, 0.5).unwrap();
+        assert!((coarse - exact).abs() < 1.0);
+
+        let mut obstacles = Obstacles::new();
+        obstacles.push(prism);
+        assert!(!obstacles.is_maneuver_free(&maneuver, 0.5));
+    }
+
+    #[test]
+    fn grazing_turn_stays_clear() {
+        assert_eq!(u_turn(1e-4).first_contact(&HalfSpace::floor(0.0)), None);
+        let cylinder = VerticalCylinder::new((0.0, 100.0), 100.0 - 1e-4, 0.0, 300.0);
+        assert_eq!(u_turn(50.0).first_contact(&cylinder), None);
+    }
+
+    #[test]
+    fn contact_lies_on_the_surface() {
+        let qi = State{x: 0.0, y: 0.0, z: 100.0, yaw: 0.0, pitch: 0.0};
+        let qf = State{x: 300.0, y: 200.0, z: 0.0, yaw: 2.0, pitch: 0.0};
+        let maneuver = DubinsManeuver3D::new(qi, qf, 40.0, (-0.3, 0.3));
+        let floor = HalfSpace::new((0.2, 0.1, 1.0), 90.0);
+        let s = maneuver.first_contact(&floor).unwrap();
+        let q = maneuver.state_at(s);
+        assert!(floor.distance((q.x, q.y, q.z)).abs() < 1e-5);
+        for i in 0..1000 {
+            let q = maneuver.state_at(s * i as f64 / 1000.0);
+            assert!(floor.distance((q.x, q.y, q.z)) > 0.0);
+        }
+    }
+}
@@ -3,8 +3,13 @@ use core::cmp::Ordering;
 use core::fmt;
 
 use crate::mod2pi;
+use crate::error::{DubinsError, Endpoint};
+use crate::validation::{validate_radius, validate_state2d};
+#[cfg(feature = "serde")]
+use crate::dubins3d::nearly_equal;
 
 #[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SegmentType {
     RIGHT,
     STRAIGHT,
@@ -24,13 +29,21 @@ impl fmt::Debug for SegmentType {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ManeuverCase {
     pub a: SegmentType,
     pub b: SegmentType,
     pub c: SegmentType
 }
 
-#[derive(Debug, Copy, Clone)]
+impl fmt::Display for ManeuverCase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}{:?}{:?}", self.a, self.b, self.c)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DubinsStruct {
     pub t: f64,
     pub p: f64,
@@ -39,11 +52,46 @@ pub struct DubinsStruct {
     pub case: ManeuverCase
 }
 
+/// Planar configuration, yaw is measured counterclockwise from the x axis
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct State2D {
+    pub x: f64,
+    pub y: f64,
+    pub yaw: f64
+}
+
+/// Single arc or straight line of a 2D Dubins maneuver
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Segment {
+    pub kind: SegmentType,
+    pub start: State2D,
+    pub end: State2D,
+    /// Arc length of the segment
+    pub length: f64,
+    /// Turning radius, infinite for straight segments
+    #[cfg_attr(feature = "serde", serde(with = "crate::infinite"))]
+    pub radius: f64,
+    /// Center of the turning circle, `None` for straight segments
+    pub center: Option<(f64, f64)>
+}
+
+/// Store 2D Dubins maneuver parameters and result
+///
+/// A deserialized maneuver is checked for valid radii and endpoints and for
+/// segment parameters that add up to its length.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "DubinsManeuver2DRaw"))]
 pub struct DubinsManeuver2D {
-    pub qi: (f64, f64, f64),
-    pub qf: (f64, f64, f64),
-    pub rhomin: f64,
-    pub maneuver: DubinsStruct
+    pub(crate) qi: (f64, f64, f64),
+    pub(crate) qf: (f64, f64, f64),
+    pub(crate) rhomin: f64,
+    /// Radii of left and right arcs, segment parameters of arcs are angles
+    /// and the straight segment is normalized by `rhomin`
+    pub(crate) radii: (f64, f64),
+    pub(crate) maneuver: DubinsStruct
 }
 
 // pub fn mod2pi(th: f64) -> f64{
@@ -55,11 +103,148 @@ pub struct DubinsManeuver2D {
 // }
 
 impl DubinsManeuver2D {
-    pub fn new(qi: (f64, f64, f64), qf: (f64, f64, f64), rhomin: f64, min_length: f64, disable_ccc: bool) -> Self {
+    /// Create the shortest planar Dubins maneuver between two configurations
+    ///
+    /// Panics if no maneuver can be constructed, see `try_new` for a fallible variant.
+    pub fn new(qi: State2D, qf: State2D, rhomin: f64) -> Self {
+        match DubinsManeuver2D::try_new(qi, qf, rhomin) {
+            Ok(maneuver) => maneuver,
+            Err(err) => panic!("unable to construct 2D Dubins maneuver: {}", err)
+        }
+    }
+
+    /// Create the shortest planar Dubins maneuver, returning an error for invalid inputs
+    pub fn try_new(qi: State2D, qf: State2D, rhomin: f64) -> Result<Self, DubinsError> {
+        validate_radius(rhomin)?;
+        validate_state2d(&qi, Endpoint::Start)?;
+        validate_state2d(&qf, Endpoint::Goal)?;
+
+        let maneuver = DubinsManeuver2D::solve(
+            (qi.x, qi.y, qi.yaw), (qf.x, qf.y, qf.yaw), rhomin, core::f64::NEG_INFINITY, false
+        );
+        if !maneuver.maneuver.length.is_finite() {
+            return Err(DubinsError::SearchFailed);
+        }
+        return Ok(maneuver);
+    }
+
+    /// Create the shortest planar Dubins maneuver with different radii for
+    /// left and right turns
+    ///
+    /// Panics if no maneuver can be constructed, see `try_new_asymmetric` for a fallible variant.
+    pub fn new_asymmetric(qi: State2D, qf: State2D, left_radius: f64, right_radius: f64) -> Self {
+        match DubinsManeuver2D::try_new_asymmetric(qi, qf, left_radius, right_radius) {
+            Ok(maneuver) => maneuver,
+            Err(err) => panic!("unable to construct 2D Dubins maneuver: {}", err)
+        }
+    }
+
+    /// Create the shortest planar Dubins maneuver with different radii for
+    /// left and right turns, returning an error for invalid inputs
+    pub fn try_new_asymmetric(qi: State2D, qf: State2D, left_radius: f64, right_radius: f64) -> Result<Self, DubinsError> {
+        validate_radius(left_radius)?;
+        validate_radius(right_radius)?;
+        validate_state2d(&qi, Endpoint::Start)?;
+        validate_state2d(&qf, Endpoint::Goal)?;
+
+        let maneuver = DubinsManeuver2D::solve_asymmetric(
+            (qi.x, qi.y, qi.yaw), (qf.x, qf.y, qf.yaw), (left_radius, right_radius), false
+        );
+        if !maneuver.maneuver.length.is_finite() {
+            return Err(DubinsError::SearchFailed);
+        }
+        return Ok(maneuver);
+    }
+
+    /// Initial configuration of the maneuver
+    pub fn start(&self) -> State2D {
+        return State2D{x: self.qi.0, y: self.qi.1, yaw: self.qi.2};
+    }
+
+    /// Final configuration of the maneuver
+    pub fn goal(&self) -> State2D {
+        return State2D{x: self.qf.0, y: self.qf.1, yaw: self.qf.2};
+    }
+
+    /// Minimum turning radius used for the arcs
+    pub fn rhomin(&self) -> f64 {
+        return self.rhomin;
+    }
+
+    /// Turning radii of left and right arcs
+    pub fn radii(&self) -> (f64, f64) {
+        return self.radii;
+    }
+
+    /// Scale from segment parameter to arc length for the given segment type
+    pub(crate) fn radius_of(&self, kind: SegmentType) -> f64 {
+        return match kind {
+            SegmentType::LEFT => self.radii.0,
+            SegmentType::RIGHT => self.radii.1,
+            _ => self.rhomin
+        };
+    }
+
+    /// Total length of the maneuver
+    pub fn length(&self) -> f64 {
+        return self.maneuver.length;
+    }
+
+    /// Word of the maneuver, e.g. LSR
+    pub fn case(&self) -> ManeuverCase {
+        return self.maneuver.case;
+    }
+
+    /// Segment parameters `t`, `p`, `q` together with length and word, arcs
+    /// are given as angles and straight segments normalized by `rhomin`
+    pub fn parameters(&self) -> DubinsStruct {
+        return self.maneuver;
+    }
+
+    /// Configuration at the given arc length, clamped to the maneuver
+    pub fn state_at(&self, offset: f64) -> State2D {
+        let q = get_coordinates_at(self, offset.max(0.0).min(self.maneuver.length));
+        return State2D{x: q.0, y: q.1, yaw: q.2};
+    }
+
+    /// Evenly spaced configurations along the maneuver, excluding the final one
+    pub fn compute_sampling(&self, number_of_samples: i32) -> Vec<State2D> {
+        let mut points: Vec<State2D> = Vec::new();
+        for sample in 0..number_of_samples {
+            let prog = self.maneuver.length * (sample as f64) / (number_of_samples as f64);
+            points.push(self.state_at(prog));
+        }
+        return points;
+    }
+
+    /// Describe the three segments of the maneuver in order
+    pub fn segments(&self) -> [Segment; 3] {
+        let kinds = [self.maneuver.case.a, self.maneuver.case.b, self.maneuver.case.c];
+        let lengths = [self.maneuver.t, self.maneuver.p, self.maneuver.q];
+
+        let mut offset = 0.0;
+        let mut start = self.start();
+        return core::array::from_fn(|i| {
+            let length = lengths[i] * self.radius_of(kinds[i]);
+            offset += length;
+            let end = if i == 2 { self.state_at(self.maneuver.length) } else { self.state_at(offset) };
+            let segment = get_segment(kinds[i], start, end, length, self.radius_of(kinds[i]));
+            start = end;
+            segment
+        });
+    }
+
+    pub(crate) fn solve(qi: (f64, f64, f64), qf: (f64, f64, f64), rhomin: f64, min_length: f64, disable_ccc: bool) -> Self {
+        return DubinsManeuver2D::solve_normalized(qi, qf, &Normalization::new(qi, qf), rhomin, min_length, disable_ccc);
+    }
+
+    /// Same as `solve` with the radius independent part computed beforehand
+    pub(crate) fn solve_normalized(qi: (f64, f64, f64), qf: (f64, f64, f64), normalization: &Normalization, rhomin: f64, min_length: f64, disable_ccc: bool) -> Self {
         let mut maneuver = DubinsManeuver2D{
             qi: qi, 
             qf: qf,
             rhomin: rhomin,
+            radii: (rhomin, rhomin),
             maneuver: DubinsStruct{
                         t: 0.0,
                         p: 0.0,
@@ -68,57 +253,8 @@ impl DubinsManeuver2D {
                         case: ManeuverCase{a: SegmentType::NONE, b: SegmentType::NONE, c: SegmentType::NONE}
                     }
         };
-    
-        let dx = maneuver.qf.0 - maneuver.qi.0;
-        let dy = maneuver.qf.1 - maneuver.qi.1;
-        let d = (dx*dx + dy*dy).sqrt() / maneuver.rhomin;
-    
-        // Normalize the problem using rotation
-        let rotation_angle = mod2pi(dy.atan2(dx));
-        let a = mod2pi(maneuver.qi.2 - rotation_angle);
-        let b = mod2pi(maneuver.qf.2 - rotation_angle);
-    
-        let (sa, ca) = (a.sin(),  a.cos());
-        let (sb, cb) = (b.sin(),  b.cos());
-    
-        let path_lsl = _lsl(&maneuver, a, b, d, sa, ca, sb, cb);
-        let path_rsr = _rsr(&maneuver, a, b, d, sa, ca, sb, cb);
-        let path_lsr = _lsr(&maneuver, a, b, d, sa, ca, sb, cb);
-        let path_rsl = _rsl(&maneuver, a, b, d, sa, ca, sb, cb);
-        
-        let path_rlr = _rlr(&maneuver, a, b, d, sa, ca, sb, cb);
-        let path_lrl = _lrl(&maneuver, a, b, d, sa, ca, sb, cb);
-        
-        let mut _paths: Vec<DubinsStruct> = Vec::new();
-        if disable_ccc {
-            _paths.extend([path_lsl, path_rsr, path_lsr, path_rsl]);
-        }
-        else {
-            _paths.extend([path_lsl, path_rsr, path_lsr, path_rsl, path_rlr, path_lrl]);
-        }
-    
-        let thresh = maneuver.rhomin * 1e-5;
-        if d.abs() < thresh && a.abs() < thresh && b.abs() < thresh {
-            let dist_2d = (maneuver.qi.0 - maneuver.qf.0).abs()
-                            .max((maneuver.qi.1 - maneuver.qf.1).abs());
-            if dist_2d < thresh {
-                let path_c = _c(&maneuver);
-                _paths.clear();
-                _paths.extend([path_c]);
-            }
-        }
-        
-        _paths.sort_by(|p1, p2| {
-            let a = p1.length;
-            let b = p2.length;
-            match (a.is_nan(), b.is_nan()) {
-                (true, true) => Ordering::Equal,
-                (true, false) => Ordering::Greater,
-                (false, true) => Ordering::Less,
-                (false, false) => a.partial_cmp(&b).unwrap(),
-            }
-        });
-       
+        let _paths = symmetric_paths(&maneuver, normalization, disable_ccc);
+
         if min_length == core::f64::NEG_INFINITY {
             maneuver.maneuver = _paths[0];
         }
@@ -134,6 +270,262 @@ impl DubinsManeuver2D {
     }
 }   
 
+impl DubinsManeuver2D {
+    /// Shortest of the six words with separate left and right radii, solved
+    /// geometrically from the turning circles
+    pub(crate) fn solve_asymmetric(qi: (f64, f64, f64), qf: (f64, f64, f64), radii: (f64, f64), disable_ccc: bool) -> Self {
+        if radii.0 == radii.1 {
+            return DubinsManeuver2D::solve(qi, qf, radii.0, core::f64::NEG_INFINITY, disable_ccc);
+        }
+        let mut maneuver = DubinsManeuver2D{
+            qi: qi,
+            qf: qf,
+            rhomin: radii.0.min(radii.1),
+            radii: radii,
+            maneuver: DubinsStruct{
+                t: 0.0,
+                p: 0.0,
+                q: 0.0,
+                length: core::f64::INFINITY,
+                case: ManeuverCase{a: SegmentType::NONE, b: SegmentType::NONE, c: SegmentType::NONE}
+            }
+        };
+
+        for path in asymmetric_paths(&maneuver, disable_ccc) {
+            if path.length < maneuver.maneuver.length {
+                maneuver.maneuver = path;
+            }
+        }
+        return maneuver;
+    }
+
+    /// Every word as a maneuver of its own, shortest first, words that do
+    /// not exist between the configurations are left out
+    pub(crate) fn solve_all(qi: (f64, f64, f64), qf: (f64, f64, f64), radii: (f64, f64), disable_ccc: bool) -> Vec<Self> {
+        let maneuver = DubinsManeuver2D{
+            qi: qi,
+            qf: qf,
+            rhomin: radii.0.min(radii.1),
+            radii: radii,
+            maneuver: DubinsStruct{
+                t: 0.0,
+                p: 0.0,
+                q: 0.0,
+                length: core::f64::INFINITY,
+                case: ManeuverCase{a: SegmentType::NONE, b: SegmentType::NONE, c: SegmentType::NONE}
+            }
+        };
+
+        let mut _paths = if radii.0 == radii.1 {
+            symmetric_paths(&maneuver, &Normalization::new(qi, qf), disable_ccc)
+        }
+        else {
+            asymmetric_paths(&maneuver, disable_ccc)
+        };
+        sort_by_length(&mut _paths);
+        return _paths.into_iter()
+            .filter(|path| path.length.is_finite())
+            .map(|path| DubinsManeuver2D{maneuver: path, ..maneuver.clone()})
+            .collect();
+    }
+}
+
+/// Part of a planar problem that does not depend on the radius, rotated so
+/// that the goal lies on the x axis
+///
+/// The 3D radius search solves the same horizontal problem for many radii
+/// and computes this once per problem.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Normalization {
+    distance: f64,
+    a: f64,
+    b: f64,
+    sa: f64,
+    ca: f64,
+    sb: f64,
+    cb: f64
+}
+
+impl Normalization {
+    pub(crate) fn new(qi: (f64, f64, f64), qf: (f64, f64, f64)) -> Self {
+        let dx = qf.0 - qi.0;
+        let dy = qf.1 - qi.1;
+
+        // Normalize the problem using rotation
+        let rotation_angle = mod2pi(dy.atan2(dx));
+        let a = mod2pi(qi.2 - rotation_angle);
+        let b = mod2pi(qf.2 - rotation_angle);
+
+        let (sa, ca) = (a.sin(),  a.cos());
+        let (sb, cb) = (b.sin(),  b.cos());
+        return Normalization{distance: (dx*dx + dy*dy).sqrt(), a: a, b: b, sa: sa, ca: ca, sb: sb, cb: cb};
+    }
+}
+
+/// All words with equal radii, sorted by length with undefined words last
+fn symmetric_paths(maneuver: &DubinsManeuver2D, normalization: &Normalization, disable_ccc: bool) -> Vec<DubinsStruct> {
+    let Normalization{distance, a, b, sa, ca, sb, cb} = *normalization;
+    let d = distance / maneuver.rhomin;
+
+    let path_lsl = _lsl(maneuver, a, b, d, sa, ca, sb, cb);
+    let path_rsr = _rsr(maneuver, a, b, d, sa, ca, sb, cb);
+    let path_lsr = _lsr(maneuver, a, b, d, sa, ca, sb, cb);
+    let path_rsl = _rsl(maneuver, a, b, d, sa, ca, sb, cb);
+
+    let path_rlr = _rlr(maneuver, a, b, d, sa, ca, sb, cb);
+    let path_lrl = _lrl(maneuver, a, b, d, sa, ca, sb, cb);
+
+    let mut _paths: Vec<DubinsStruct> = Vec::new();
+    if disable_ccc {
+        _paths.extend([path_lsl, path_rsr, path_lsr, path_rsl]);
+    }
+    else {
+        _paths.extend([path_lsl, path_rsr, path_lsr, path_rsl, path_rlr, path_lrl]);
+    }
+
+    let thresh = maneuver.rhomin * 1e-5;
+    if d.abs() < thresh && a.abs() < thresh && b.abs() < thresh {
+        let dist_2d = (maneuver.qi.0 - maneuver.qf.0).abs()
+                        .max((maneuver.qi.1 - maneuver.qf.1).abs());
+        if dist_2d < thresh {
+            let path_c = _c(maneuver);
+            _paths.clear();
+            _paths.extend([path_c]);
+        }
+    }
+
+    sort_by_length(&mut _paths);
+    return _paths;
+}
+
+/// All words with separate left and right radii, in a fixed order
+fn asymmetric_paths(maneuver: &DubinsManeuver2D, disable_ccc: bool) -> Vec<DubinsStruct> {
+    let mut _paths = vec![
+        _csc_asymmetric(maneuver, SegmentType::LEFT, SegmentType::LEFT),
+        _csc_asymmetric(maneuver, SegmentType::RIGHT, SegmentType::RIGHT),
+        _csc_asymmetric(maneuver, SegmentType::LEFT, SegmentType::RIGHT),
+        _csc_asymmetric(maneuver, SegmentType::RIGHT, SegmentType::LEFT)
+    ];
+    if !disable_ccc {
+        _paths.push(_ccc_asymmetric(maneuver, SegmentType::RIGHT));
+        _paths.push(_ccc_asymmetric(maneuver, SegmentType::LEFT));
+    }
+    return _paths;
+}
+
+/// Sort words by length, NaN lengths last
+fn sort_by_length(paths: &mut [DubinsStruct]) {
+    paths.sort_by(|p1, p2| {
+        let a = p1.length;
+        let b = p2.length;
+        match (a.is_nan(), b.is_nan()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => a.partial_cmp(&b).unwrap(),
+        }
+    });
+}
+
+/// Center of the turning circle of the given direction through a configuration
+fn turning_center(q: (f64, f64, f64), kind: SegmentType, radius: f64) -> (f64, f64) {
+    let sign = if kind == SegmentType::LEFT { 1.0 } else { -1.0 };
+    return (q.0 - sign * radius * q.2.sin(), q.1 + sign * radius * q.2.cos());
+}
+
+/// Angle turned from heading `from` to heading `to` along an arc of the given direction
+fn turning_angle(from: f64, to: f64, kind: SegmentType) -> f64 {
+    if kind == SegmentType::LEFT {
+        return mod2pi(to - from);
+    }
+    return mod2pi(from - to);
+}
+
+fn _csc_asymmetric(maneuver: &DubinsManeuver2D, a: SegmentType, c: SegmentType) -> DubinsStruct {
+    let ra = maneuver.radius_of(a);
+    let rc = maneuver.radius_of(c);
+    let sa = if a == SegmentType::LEFT { 1.0 } else { -1.0 };
+    let sc = if c == SegmentType::LEFT { 1.0 } else { -1.0 };
+
+    let o1 = turning_center(maneuver.qi, a, ra);
+    let o2 = turning_center(maneuver.qf, c, rc);
+    let diff = (o2.0 - o1.0, o2.1 - o1.1);
+    let center_distance = (diff.0 * diff.0 + diff.1 * diff.1).sqrt();
+
+    // The straight segment leaves the first circle and touches the second
+    // one with the same heading, offset sideways by the signed radii
+    let offset = sa * ra - sc * rc;
+    let mut t = core::f64::INFINITY;
+    let mut p = core::f64::INFINITY;
+    let mut q = core::f64::INFINITY;
+    if center_distance < 1e-12 && offset.abs() < 1e-12 {
+        t = 0.0;
+        p = 0.0;
+        q = turning_angle(maneuver.qi.2, maneuver.qf.2, c);
+    }
+    else if offset.abs() <= center_distance {
+        let heading = diff.1.atan2(diff.0) + (offset / center_distance).asin();
+        t = turning_angle(maneuver.qi.2, heading, a);
+        p = (center_distance * center_distance - offset * offset).max(0.0).sqrt() / maneuver.rhomin;
+        q = turning_angle(heading, maneuver.qf.2, c);
+    }
+
+    return DubinsStruct {
+        t: t,
+        p: p,
+        q: q,
+        length: t * ra + p * maneuver.rhomin + q * rc,
+        case: ManeuverCase {a: a, b: SegmentType::STRAIGHT, c: c}
+    };
+}
+
+fn _ccc_asymmetric(maneuver: &DubinsManeuver2D, a: SegmentType) -> DubinsStruct {
+    let b = if a == SegmentType::LEFT { SegmentType::RIGHT } else { SegmentType::LEFT };
+    let ra = maneuver.radius_of(a);
+    let rb = maneuver.radius_of(b);
+    let sa = if a == SegmentType::LEFT { 1.0 } else { -1.0 };
+
+    let o1 = turning_center(maneuver.qi, a, ra);
+    let o3 = turning_center(maneuver.qf, a, ra);
+    let diff = (o3.0 - o1.0, o3.1 - o1.1);
+    let center_distance = (diff.0 * diff.0 + diff.1 * diff.1).sqrt();
+
+    let mut best = DubinsStruct {
+        t: core::f64::INFINITY,
+        p: core::f64::INFINITY,
+        q: core::f64::INFINITY,
+        length: core::f64::INFINITY,
+        case: ManeuverCase {a: a, b: b, c: a}
+    };
+    if center_distance < 1e-12 || center_distance > 2.0 * (ra + rb) {
+        return best;
+    }
+
+    // The middle circle touches both outer circles from the outside
+    let height = ((ra + rb) * (ra + rb) - center_distance * center_distance / 4.0).max(0.0).sqrt();
+    let mid = (o1.0 + diff.0 / 2.0, o1.1 + diff.1 / 2.0);
+    let normal = (-diff.1 / center_distance, diff.0 / center_distance);
+    for side in [1.0, -1.0] {
+        let om = (mid.0 + side * height * normal.0, mid.1 + side * height * normal.1);
+        // Heading where a circle around `o` is left towards `om`
+        let heading_towards = |o: (f64, f64)| -> f64 {
+            let d = (om.0 - o.0, om.1 - o.1);
+            let n = (sa * d.0, sa * d.1);
+            return n.0.atan2(-n.1);
+        };
+        let h1 = heading_towards(o1);
+        let h2 = heading_towards(o3);
+        let t = turning_angle(maneuver.qi.2, h1, a);
+        let p = turning_angle(h1, h2, b);
+        let q = turning_angle(h2, maneuver.qf.2, a);
+        let length = t * ra + p * rb + q * ra;
+        if length < best.length {
+            best = DubinsStruct {t: t, p: p, q: q, length: length, case: best.case};
+        }
+    }
+    return best;
+}
+
 fn _lsl(maneuver: &DubinsManeuver2D, a: f64, b: f64, d: f64, sa: f64, ca: f64, sb: f64, cb: f64) -> DubinsStruct {
     let aux = (cb - ca).atan2(d + sa - sb);
     let t  = mod2pi(-a + aux);
@@ -278,33 +670,88 @@ fn _c(maneuver: &DubinsManeuver2D) -> DubinsStruct{
     return ds;
 }
 
-pub fn get_coordinates_at(maneuver: &DubinsManeuver2D, offset: f64) -> (f64,f64,f64) {
-    let n_offset = offset / maneuver.rhomin;
+/// Start configurations and lengths of the three segments, computed once so
+/// that repeated evaluations of a maneuver stay cheap
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct SegmentStarts {
+    q0: (f64, f64, f64),
+    q1: (f64, f64, f64),
+    q2: (f64, f64, f64),
+    l1: f64,
+    l2: f64
+}
 
-    let qi: (f64,f64,f64) = (0.0, 0.0, maneuver.qi.2);
+impl SegmentStarts {
+    pub(crate) fn new(maneuver: &DubinsManeuver2D) -> Self {
+        let case = maneuver.maneuver.case;
+        let l1 = maneuver.maneuver.t * maneuver.radius_of(case.a);
+        let l2 = maneuver.maneuver.p * maneuver.radius_of(case.b);
+        let q0 = maneuver.qi;
+        let q1 = get_position_in_scaled_segment(l1, q0, case.a, maneuver.radius_of(case.a));
+        let q2 = get_position_in_scaled_segment(l2, q1, case.b, maneuver.radius_of(case.b));
+        return SegmentStarts{q0: q0, q1: q1, q2: q2, l1: l1, l2: l2};
+    }
+}
 
-    let l1 = maneuver.maneuver.t;
-    let l2 = maneuver.maneuver.p;
-    let q1 = get_position_in_segment(l1, qi, maneuver.maneuver.case.a);
-    let q2 = get_position_in_segment(l2, q1, maneuver.maneuver.case.b);
+pub fn get_coordinates_at(maneuver: &DubinsManeuver2D, offset: f64) -> (f64,f64,f64) {
+    return get_coordinates_with(maneuver, &SegmentStarts::new(maneuver), offset);
+}
+
+pub(crate) fn get_coordinates_with(maneuver: &DubinsManeuver2D, starts: &SegmentStarts, offset: f64) -> (f64,f64,f64) {
+    let case = maneuver.maneuver.case;
 
     let mut q: (f64, f64, f64);
-    if n_offset < l1 {
-        q = get_position_in_segment(n_offset, qi, maneuver.maneuver.case.a);
+    if offset < starts.l1 {
+        q = get_position_in_scaled_segment(offset, starts.q0, case.a, maneuver.radius_of(case.a));
     }
-    else if n_offset < (l1 + l2) {
-        q = get_position_in_segment(n_offset - l1, q1, maneuver.maneuver.case.b);
+    else if offset < (starts.l1 + starts.l2) {
+        q = get_position_in_scaled_segment(offset - starts.l1, starts.q1, case.b, maneuver.radius_of(case.b));
     }
     else {
-        q = get_position_in_segment(n_offset - l1 - l2, q2, maneuver.maneuver.case.c);
+        q = get_position_in_scaled_segment(offset - starts.l1 - starts.l2, starts.q2, case.c, maneuver.radius_of(case.c));
     }
-    q.0 = q.0 * maneuver.rhomin + maneuver.qi.0;
-    q.1 = q.1 * maneuver.rhomin + maneuver.qi.1;
     q.2 = mod2pi(q.2);
 
     return q;
 }
 
+/// Signed curvature at `offset`, positive while turning left
+///
+/// At a junction the curvature of the following segment is returned.
+pub(crate) fn get_curvature_at(maneuver: &DubinsManeuver2D, offset: f64) -> f64 {
+    let case = maneuver.maneuver.case;
+    let l1 = maneuver.maneuver.t * maneuver.radius_of(case.a);
+    let l2 = maneuver.maneuver.p * maneuver.radius_of(case.b);
+
+    let kind = if offset < l1 {
+        case.a
+    }
+    else if offset < l1 + l2 {
+        case.b
+    }
+    else {
+        case.c
+    };
+
+    return match kind {
+        SegmentType::LEFT => 1.0 / maneuver.radius_of(kind),
+        SegmentType::RIGHT => -1.0 / maneuver.radius_of(kind),
+        _ => 0.0
+    };
+}
+
+/// Advance the distance `offset` along a segment whose arcs have the given radius
+fn get_position_in_scaled_segment(offset: f64, qi: (f64,f64,f64), case: SegmentType, radius: f64) -> (f64,f64,f64) {
+    let q = get_position_in_segment(offset / radius, (0.0, 0.0, qi.2), case);
+    return (qi.0 + q.0 * radius, qi.1 + q.1 * radius, q.2);
+}
+
+/// Advance along a segment driven forward for `direction > 0` and in
+/// reverse for `direction < 0`, `offset` is the unsigned normalized distance
+pub(crate) fn get_position_in_directed_segment(offset: f64, qi: (f64,f64,f64), case: SegmentType, direction: f64) -> (f64,f64,f64) {
+    return get_position_in_segment(offset * direction.signum(), qi, case);
+}
+
 fn get_position_in_segment(offset: f64, qi: (f64,f64,f64), case: SegmentType) -> (f64,f64,f64){
     let mut q: (f64, f64, f64) = (0.0, 0.0, 0.0);
     if case == SegmentType::LEFT {
@@ -329,4 +776,232 @@ fn get_position_in_segment(offset: f64, qi: (f64,f64,f64), case: SegmentType) ->
         );
     }
     return q;
-}
\ No newline at end of file
+}
+
+pub(crate) fn get_segment(kind: SegmentType, start: State2D, end: State2D, length: f64, rhomin: f64) -> Segment {
+    let (radius, center) = match kind {
+        SegmentType::LEFT => (rhomin, Some((start.x - rhomin * start.yaw.sin(), start.y + rhomin * start.yaw.cos()))),
+        SegmentType::RIGHT => (rhomin, Some((start.x + rhomin * start.yaw.sin(), start.y - rhomin * start.yaw.cos()))),
+        _ => (core::f64::INFINITY, None)
+    };
+    return Segment{kind: kind, start: start, end: end, length: length, radius: radius, center: center};
+}
+
+/// Serialized form of `DubinsManeuver2D`, checked before it is used
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct DubinsManeuver2DRaw {
+    qi: (f64, f64, f64),
+    qf: (f64, f64, f64),
+    rhomin: f64,
+    radii: (f64, f64),
+    maneuver: DubinsStruct
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<DubinsManeuver2DRaw> for DubinsManeuver2D {
+    type Error = DubinsError;
+
+    fn try_from(raw: DubinsManeuver2DRaw) -> Result<Self, DubinsError> {
+        validate_radius(raw.radii.0)?;
+        validate_radius(raw.radii.1)?;
+        if raw.rhomin != raw.radii.0.min(raw.radii.1) {
+            return Err(DubinsError::InconsistentData("rhomin is not the smaller turn radius"));
+        }
+        validate_state2d(&State2D{x: raw.qi.0, y: raw.qi.1, yaw: raw.qi.2}, Endpoint::Start)?;
+        validate_state2d(&State2D{x: raw.qf.0, y: raw.qf.1, yaw: raw.qf.2}, Endpoint::Goal)?;
+
+        let parameters = raw.maneuver;
+        if [parameters.t, parameters.p, parameters.q].iter().any(|value| !(value.is_finite() && *value >= 0.0)) {
+            return Err(DubinsError::InconsistentData("segment parameters must be finite and not negative"));
+        }
+        let case = parameters.case;
+        if [case.a, case.b, case.c].contains(&SegmentType::NONE) {
+            return Err(DubinsError::InconsistentData("word has no segment type"));
+        }
+
+        let maneuver = DubinsManeuver2D{qi: raw.qi, qf: raw.qf, rhomin: raw.rhomin, radii: raw.radii, maneuver: parameters};
+        let length = parameters.t * maneuver.radius_of(case.a)
+            + parameters.p * maneuver.radius_of(case.b)
+            + parameters.q * maneuver.radius_of(case.c);
+        if !nearly_equal(parameters.length, length) {
+            return Err(DubinsError::InconsistentData("length differs from the segment parameters"));
+        }
+        return Ok(maneuver);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dubins3d::{DubinsManeuver3D, TurnRadii};
+    use crate::{State, mod_pi};
+
+    fn goals() -> Vec<(f64, f64, f64)> {
+        let mut goals = Vec::new();
+        for i in 0..7 {
+            for j in 0..7 {
+                for k in 0..6 {
+                    goals.push((-60.0 + 20.0 * i as f64, -60.0 + 20.0 * j as f64, -3.0 + 1.1 * k as f64));
+                }
+            }
+        }
+        return goals;
+    }
+
+    fn ends_at(maneuver: &DubinsManeuver2D, qf: (f64, f64, f64)) -> bool {
+        let end = maneuver.state_at(maneuver.length());
+        return (end.x - qf.0).hypot(end.y - qf.1) < 1e-6 && mod_pi(end.yaw - qf.2).abs() < 1e-6;
+    }
+
+    #[test]
+    fn every_asymmetric_word_ends_at_the_goal() {
+        let qi = (0.0, 0.0, 0.3);
+        for radii in [(10.0, 25.0), (30.0, 8.0)] {
+            let mut words = 0;
+            for qf in goals() {
+                for maneuver in DubinsManeuver2D::solve_all(qi, qf, radii, false) {
+                    assert!(ends_at(&maneuver, qf), "{} misses {:?}", maneuver.case(), qf);
+                    words += 1;
+                }
+            }
+            assert!(words > 4 * goals().len());
+        }
+    }
+
+    #[test]
+    fn equal_radii_reproduce_the_symmetric_solver() {
+        let qi = State2D{x: 0.0, y: 0.0, yaw: 0.3};
+        for qf in goals() {
+            let goal = State2D{x: qf.0, y: qf.1, yaw: qf.2};
+            let symmetric = DubinsManeuver2D::new(qi, goal, 15.0);
+            let asymmetric = DubinsManeuver2D::new_asymmetric(qi, goal, 15.0, 15.0);
+            assert_eq!(asymmetric.parameters(), symmetric.parameters());
+
+            // The geometric solver agrees with the closed-form one as well,
+            // except at the start itself where the latter flies a full circle
+            if qf.0 == 0.0 && qf.1 == 0.0 && mod_pi(qf.2 - qi.yaw).abs() < 1e-9 {
+                continue;
+            }
+            let geometric = asymmetric_paths(&symmetric, false).iter()
+                .map(|path| path.length)
+                .filter(|length| length.is_finite())
+                .fold(core::f64::INFINITY, f64::min);
+            assert!((geometric - symmetric.parameters().length).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn asymmetric_solver_picks_the_shortest_word() {
+        let qi = (0.0, 0.0, 0.3);
+        for qf in goals() {
+            let best = DubinsManeuver2D::solve_asymmetric(qi, qf, (10.0, 25.0), false);
+            let all = DubinsManeuver2D::solve_all(qi, qf, (10.0, 25.0), false);
+            let shortest = all.iter().map(|m| m.length()).fold(core::f64::INFINITY, f64::min);
+            assert_eq!(best.length(), shortest);
+            assert_eq!(best.case(), all[0].case());
+        }
+    }
+
+    #[test]
+    fn asymmetric_3d_maneuver_ends_at_the_goal() {
+        let radii = TurnRadii{left: 30.0, right: 50.0, up: 60.0, down: 35.0};
+        let qi = State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+        let qf = State{x: 200.0, y: -150.0, z: 40.0, yaw: 2.0, pitch: 0.1};
+        let maneuver = DubinsManeuver3D::new_asymmetric(qi, qf, radii, (-0.3, 0.3));
+        let end = maneuver.state_at(maneuver.length());
+        assert!((end.x - qf.x).abs() < 1e-6 && (end.y - qf.y).abs() < 1e-6 && (end.z - qf.z).abs() < 1e-6);
+        assert!(maneuver.length() >= DubinsManeuver3D::new(qi, qf, radii.min(), (-0.3, 0.3)).length() - 1e-9);
+    }
+
+    fn same_state(a: State2D, b: State2D) -> bool {
+        return (a.x - b.x).hypot(a.y - b.y) < 1e-6 && mod_pi(a.yaw - b.yaw).abs() < 1e-6;
+    }
+
+    #[test]
+    fn segments_chain_from_start_to_goal() {
+        let qi = State2D{x: 0.0, y: 0.0, yaw: 0.3};
+        for qf in goals() {
+            let goal = State2D{x: qf.0, y: qf.1, yaw: qf.2};
+            for maneuver in [DubinsManeuver2D::new(qi, goal, 15.0), DubinsManeuver2D::new_asymmetric(qi, goal, 10.0, 25.0)] {
+                let segments = maneuver.segments();
+                assert_eq!(segments[0].start, qi);
+                for pair in segments.windows(2) {
+                    assert_eq!(pair[0].end, pair[1].start);
+                }
+                assert!(same_state(segments[2].end, goal), "{} misses {:?}", maneuver.case(), qf);
+                let total: f64 = segments.iter().map(|segment| segment.length).sum();
+                assert!((total - maneuver.length()).abs() < 1e-9 * maneuver.length().max(1.0));
+            }
+        }
+    }
+
+    #[test]
+    fn state_at_ends_are_start_and_goal() {
+        let qi = State2D{x: 0.0, y: 0.0, yaw: 0.3};
+        for qf in goals() {
+            let goal = State2D{x: qf.0, y: qf.1, yaw: qf.2};
+            let maneuver = DubinsManeuver2D::new(qi, goal, 15.0);
+            assert_eq!(maneuver.state_at(0.0), qi);
+            assert!(same_state(maneuver.state_at(maneuver.length()), goal));
+            // Arc lengths outside the maneuver are clamped
+            assert_eq!(maneuver.state_at(-5.0), qi);
+            assert_eq!(maneuver.state_at(maneuver.length() + 5.0), maneuver.state_at(maneuver.length()));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    fn maneuver() -> DubinsManeuver2D {
+        let qi = State2D{x: 0.0, y: 0.0, yaw: 0.0};
+        let qf = State2D{x: 200.0, y: 100.0, yaw: 2.0};
+        return DubinsManeuver2D::new_asymmetric(qi, qf, 30.0, 50.0);
+    }
+
+    #[test]
+    fn round_trip() {
+        let maneuver = maneuver();
+        let json = serde_json::to_string(&maneuver).unwrap();
+        let restored: DubinsManeuver2D = serde_json::from_str(&json).unwrap();
+        let (a, b) = (restored.parameters(), maneuver.parameters());
+        assert_eq!(a.case, b.case);
+        assert!(nearly_equal(a.t, b.t) && nearly_equal(a.p, b.p) && nearly_equal(a.q, b.q) && nearly_equal(a.length, b.length));
+        assert_eq!(restored.radii(), maneuver.radii());
+    }
+
+    #[test]
+    fn straight_segment_round_trips() {
+        let straight = maneuver().segments().into_iter().find(|segment| segment.kind == SegmentType::STRAIGHT).unwrap();
+        assert!(straight.radius.is_infinite());
+        let json = serde_json::to_string(&straight).unwrap();
+        let restored: Segment = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, straight);
+    }
+
+    #[test]
+    fn rejects_invalid_maneuvers() {
+        let raw = |radii: (f64, f64), parameters: DubinsStruct| DubinsManeuver2DRaw {
+            qi: (0.0, 0.0, 0.0),
+            qf: (200.0, 100.0, 2.0),
+            rhomin: radii.0.min(radii.1),
+            radii: radii,
+            maneuver: parameters
+        };
+        let parameters = maneuver().parameters();
+        assert!(DubinsManeuver2D::try_from(raw((30.0, 50.0), parameters)).is_ok());
+        assert!(DubinsManeuver2D::try_from(raw((core::f64::NAN, 50.0), parameters)).is_err());
+        assert!(DubinsManeuver2D::try_from(raw((30.0, -50.0), parameters)).is_err());
+        assert!(DubinsManeuver2D::try_from(raw((30.0, 50.0), DubinsStruct{t: -parameters.t, ..parameters})).is_err());
+        assert!(DubinsManeuver2D::try_from(raw((30.0, 50.0), DubinsStruct{p: core::f64::NAN, ..parameters})).is_err());
+        assert!(DubinsManeuver2D::try_from(raw((30.0, 50.0), DubinsStruct{length: 2.0 * parameters.length, ..parameters})).is_err());
+        let case = ManeuverCase{a: SegmentType::NONE, ..parameters.case};
+        assert!(DubinsManeuver2D::try_from(raw((30.0, 50.0), DubinsStruct{case: case, ..parameters})).is_err());
+
+        let mut json = serde_json::to_value(maneuver()).unwrap();
+        json["rhomin"] = serde_json::json!(10.0);
+        assert!(serde_json::from_value::<DubinsManeuver2D>(json).is_err());
+    }
+}
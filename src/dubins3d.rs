@@ -1,86 +1,227 @@
 #![allow(dead_code)]
 
-use crate::State;
+use crate::{PI, State, mod_pi};
+use crate::error::{DubinsError, Endpoint};
+use crate::validation::{validate, validate_altitude, validate_altitude_limits, validate_radius};
 use crate::dubins2d::{
     DubinsManeuver2D, 
+    Segment,
     SegmentType, 
     ManeuverCase,
-    get_coordinates_at
+    Normalization,
+    get_coordinates_at,
+    get_curvature_at
 };
 use crate::vertical;
+use crate::samples::Samples;
+
+/// Upper limit on how many times the horizontal radius is doubled while
+/// looking for a first feasible maneuver
+const MAX_RADIUS_DOUBLINGS: i32 = 64;
+
+/// Horizontal radius scale beyond which widening stops under altitude limits
+///
+/// The vertical radius is the radius over `sqrt(1 - 1 / b^2)`, which is then
+/// within 1e-12 of its limit. A wider horizontal turn no longer changes the
+/// vertical maneuver and cannot bring it within the limits, while far larger
+/// scales leave only rounding noise in the vertical component.
+const MAX_ZLIMS_RADIUS_SCALE: f64 = 1e6;
+
+/// Minimum turning radius for each turn direction of a 3D maneuver
+///
+/// Left and right apply to the horizontal plane, up and down to pulling up
+/// and pushing over in the vertical plane.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TurnRadii {
+    pub left: f64,
+    pub right: f64,
+    pub up: f64,
+    pub down: f64
+}
+
+impl TurnRadii {
+    /// Same radius in every direction
+    pub fn symmetric(rhomin: f64) -> Self {
+        return TurnRadii{left: rhomin, right: rhomin, up: rhomin, down: rhomin};
+    }
+
+    /// Smallest of the four radii, a symmetric maneuver with this radius is
+    /// never longer than the asymmetric one
+    pub fn min(&self) -> f64 {
+        return self.left.min(self.right).min(self.up).min(self.down);
+    }
+}
 
 /// Store 3D Dubins maneuver parameters and result
+///
+/// A deserialized maneuver is checked against the constructor's input
+/// validation and for components that connect its endpoints.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "DubinsManeuver3DRaw"))]
 pub struct DubinsManeuver3D {
     qi: State,
     qf: State,
     rhomin: f64,
+    radii: TurnRadii,
     pitchlims: (f64, f64),
+    zlims: Option<(f64, f64)>,
     path: Vec<DubinsManeuver2D>,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::infinite::serialize"))]
     length: f64
 }
 
 impl DubinsManeuver3D {
-    /// Create a new Dubins maneuver with desired 
+    /// Create a new Dubins maneuver with desired turning radius and pitch limits
+    ///
+    /// Panics if no maneuver can be constructed, see `try_new` for a fallible variant.
     pub fn new(qi: State, qf: State, rhomin: f64, pitchlims: (f64, f64)) -> DubinsManeuver3D {
+        match DubinsManeuver3D::try_new(qi, qf, rhomin, pitchlims) {
+            Ok(maneuver) => maneuver,
+            Err(err) => panic!("unable to construct 3D Dubins maneuver: {}", err)
+        }
+    }
+
+    /// Create a new Dubins maneuver, returning an error if no maneuver can be constructed
+    pub fn try_new(qi: State, qf: State, rhomin: f64, pitchlims: (f64, f64)) -> Result<DubinsManeuver3D, DubinsError> {
+        validate(&qi, &qf, rhomin, pitchlims)?;
+        return DubinsManeuver3D::solve(qi, qf, rhomin, pitchlims);
+    }
+
+    /// Create a new Dubins maneuver with a separate turning radius per turn direction
+    ///
+    /// Panics if no maneuver can be constructed, see `try_new_asymmetric` for a fallible variant.
+    pub fn new_asymmetric(qi: State, qf: State, radii: TurnRadii, pitchlims: (f64, f64)) -> DubinsManeuver3D {
+        match DubinsManeuver3D::try_new_asymmetric(qi, qf, radii, pitchlims) {
+            Ok(maneuver) => maneuver,
+            Err(err) => panic!("unable to construct 3D Dubins maneuver: {}", err)
+        }
+    }
+
+    /// Create a new Dubins maneuver with a separate turning radius per turn
+    /// direction, returning an error if no maneuver can be constructed
+    pub fn try_new_asymmetric(qi: State, qf: State, radii: TurnRadii, pitchlims: (f64, f64)) -> Result<DubinsManeuver3D, DubinsError> {
+        validate_radius(radii.left)?;
+        validate_radius(radii.right)?;
+        validate_radius(radii.up)?;
+        validate_radius(radii.down)?;
+        validate(&qi, &qf, radii.min(), pitchlims)?;
+        return DubinsManeuver3D::solve_asymmetric(qi, qf, radii, pitchlims);
+    }
+
+    /// Create a new Dubins maneuver that stays between the altitudes `zlims`
+    ///
+    /// Panics if no maneuver can be constructed, see `try_new_with_zlims` for a fallible variant.
+    pub fn new_with_zlims(qi: State, qf: State, rhomin: f64, pitchlims: (f64, f64), zlims: (f64, f64)) -> DubinsManeuver3D {
+        match DubinsManeuver3D::try_new_with_zlims(qi, qf, rhomin, pitchlims, zlims) {
+            Ok(maneuver) => maneuver,
+            Err(err) => panic!("unable to construct 3D Dubins maneuver: {}", err)
+        }
+    }
+
+    /// Create a new Dubins maneuver that stays between the altitudes `zlims`,
+    /// returning an error if no maneuver can be constructed
+    ///
+    /// Where the shortest vertical word leaves the altitude range, longer
+    /// words are tried, so the maneuver may be longer than without limits.
+    /// Either limit may be infinite.
+    pub fn try_new_with_zlims(qi: State, qf: State, rhomin: f64, pitchlims: (f64, f64), zlims: (f64, f64)) -> Result<DubinsManeuver3D, DubinsError> {
+        validate(&qi, &qf, rhomin, pitchlims)?;
+        validate_altitude_limits(zlims)?;
+        validate_altitude(&qi, Endpoint::Start, zlims)?;
+        validate_altitude(&qf, Endpoint::Goal, zlims)?;
+        return DubinsManeuver3D::solve_within(qi, qf, TurnRadii::symmetric(rhomin), pitchlims, Some(zlims));
+    }
+
+    /// Radius search behind `try_new`, expects validated inputs
+    pub(crate) fn solve(qi: State, qf: State, rhomin: f64, pitchlims: (f64, f64)) -> Result<DubinsManeuver3D, DubinsError> {
+        return DubinsManeuver3D::solve_asymmetric(qi, qf, TurnRadii::symmetric(rhomin), pitchlims);
+    }
+
+    /// Radius search behind `try_new_asymmetric`, expects validated inputs
+    pub(crate) fn solve_asymmetric(qi: State, qf: State, radii: TurnRadii, pitchlims: (f64, f64)) -> Result<DubinsManeuver3D, DubinsError> {
+        return DubinsManeuver3D::solve_within(qi, qf, radii, pitchlims, None);
+    }
+
+    /// Radius search keeping the maneuver within optional altitude limits,
+    /// expects validated inputs
+    pub(crate) fn solve_within(qi: State, qf: State, radii: TurnRadii, pitchlims: (f64, f64), zlims: Option<(f64, f64)>) -> Result<DubinsManeuver3D, DubinsError> {
+        return DubinsManeuver3D::solve_lateral(qi, qf, radii, pitchlims, zlims, None);
+    }
+
+    /// Radius search that only uses the horizontal word `lateral` if given,
+    /// expects validated inputs
+    pub(crate) fn solve_lateral(qi: State, qf: State, radii: TurnRadii, pitchlims: (f64, f64), zlims: Option<(f64, f64)>, lateral: Option<ManeuverCase>) -> Result<DubinsManeuver3D, DubinsError> {
+        let (qi, qf) = (wrap_pitch(qi), wrap_pitch(qf));
         let mut maneuver = DubinsManeuver3D {
             qi: qi,
             qf: qf,
-            rhomin: rhomin,
+            rhomin: radii.min(),
+            radii: radii,
             pitchlims: pitchlims,
+            zlims: zlims,
             path: Vec::new(),
             length: -1.0
         };
     
-        let a = 1.0;
+        // Only the radius changes during the search, the rest of the
+        // horizontal problem is shared by every attempt
+        let normalization = Normalization::new((qi.x, qi.y, qi.yaw), (qf.x, qf.y, qf.yaw));
         let mut b = 1.0;
+        let mut fb = try_to_construct(&maneuver, &normalization, b, lateral);
     
-        let fa = try_to_construct(&maneuver, maneuver.rhomin * a);
-        let mut fb = try_to_construct(&maneuver, maneuver.rhomin * b);
-    
+        let mut doublings = 0;
         while fb.len() < 2 {
-            b *= 2.0;
-            fb = try_to_construct(&maneuver, maneuver.rhomin * b);
-        }
-    
-        if fa.len() > 0 {
-            maneuver.path = fa;
-        }
-        else {
-            if fb.len() < 2 {
-                // return Err(
-                //     Error::new(ErrorKind::)
-                // );
-                return maneuver;
+            if doublings >= MAX_RADIUS_DOUBLINGS {
+                return Err(DubinsError::SearchFailed);
             }
+            if maneuver.zlims.is_some() && b >= MAX_ZLIMS_RADIUS_SCALE {
+                return Err(DubinsError::SearchFailed);
+            }
+            b *= 2.0;
+            doublings += 1;
+            fb = try_to_construct(&maneuver, &normalization, b, lateral);
         }
     
         let mut step: f64 = 0.1;
         while step.abs() > 1e-10 {
             let c = (b + step).max(1.0);
-            let fc = try_to_construct(&maneuver, maneuver.rhomin * c);
-            if fc.len() > 0 {
-                if fc[1].maneuver.length < fb[1].maneuver.length {
-                    b = c;
-                    fb = fc;
-                    step *= 2.0;
-                    continue;
-                }
+            let fc = try_to_construct(&maneuver, &normalization, c, lateral);
+            if !fc.is_empty() && fc[1].maneuver.length < fb[1].maneuver.length {
+                b = c;
+                fb = fc;
+                step *= 2.0;
+                continue;
             }
             step *= -0.1;
         }
-        maneuver.path.clear();
+
+        maneuver.length = fb[1].maneuver.length;
+        if !maneuver.length.is_finite() {
+            return Err(DubinsError::SearchFailed);
+        }
         maneuver.path.extend(fb);
-        maneuver.length = maneuver.path[1].maneuver.length;
     
-        return maneuver;
+        return Ok(maneuver);
     }
     
-    pub fn get_lower_bound(qi: State, qf: State, rhomin: f64, pitchlims: (f64, f64)) -> Self {
+    /// Compute a maneuver whose length is a lower bound on the optimal length
+    pub fn get_lower_bound(qi: State, qf: State, rhomin: f64, pitchlims: (f64, f64)) -> Result<Self, DubinsError> {
+        validate(&qi, &qf, rhomin, pitchlims)?;
+        return Ok(DubinsManeuver3D::solve_lower_bound(qi, qf, rhomin, pitchlims));
+    }
+
+    /// Lower bound behind `get_lower_bound`, expects validated inputs
+    pub(crate) fn solve_lower_bound(qi: State, qf: State, rhomin: f64, pitchlims: (f64, f64)) -> Self {
+        let (qi, qf) = (wrap_pitch(qi), wrap_pitch(qf));
         let mut maneuver = DubinsManeuver3D {
             qi: qi,
             qf: qf,
             rhomin: rhomin,
+            radii: TurnRadii::symmetric(rhomin),
             pitchlims: pitchlims,
+            zlims: None,
             path: Vec::new(),
             length: -1.0
         };
@@ -89,11 +230,11 @@ impl DubinsManeuver3D {
     
         let qi2d = (maneuver.qi.x, maneuver.qi.y, maneuver.qi.yaw);
         let qf2d = (maneuver.qf.x, maneuver.qf.y, maneuver.qf.yaw);
-        let dlat = DubinsManeuver2D::new(qi2d, qf2d, spiral_radius, core::f64::NEG_INFINITY, false);
+        let dlat = DubinsManeuver2D::solve(qi2d, qf2d, spiral_radius, core::f64::NEG_INFINITY, false);
     
         let qi3d = (0.0, maneuver.qi.z, maneuver.qi.pitch);
         let qf3d = (dlat.maneuver.length, maneuver.qf.z, maneuver.qf.pitch);
-        let dlon = vertical::get_vertical(qi3d, qf3d, maneuver.rhomin, maneuver.pitchlims);
+        let dlon = vertical::get_vertical(qi3d, qf3d, (maneuver.rhomin, maneuver.rhomin), maneuver.pitchlims);
     
         if dlon.maneuver.case.a == SegmentType::NONE {
             maneuver.length = 0.0;
@@ -105,17 +246,27 @@ impl DubinsManeuver3D {
         return maneuver;
     }
     
-    pub fn get_upper_bound(qi: State, qf: State, rhomin: f64, pitchlims: (f64, f64)) -> Self {
+    /// Compute a maneuver whose length is an upper bound on the optimal length
+    pub fn get_upper_bound(qi: State, qf: State, rhomin: f64, pitchlims: (f64, f64)) -> Result<Self, DubinsError> {
+        validate(&qi, &qf, rhomin, pitchlims)?;
+        return Ok(DubinsManeuver3D::solve_upper_bound(qi, qf, rhomin, pitchlims));
+    }
+
+    /// Upper bound behind `get_upper_bound`, expects validated inputs
+    pub(crate) fn solve_upper_bound(qi: State, qf: State, rhomin: f64, pitchlims: (f64, f64)) -> Self {
+        let (qi, qf) = (wrap_pitch(qi), wrap_pitch(qf));
         let mut maneuver = DubinsManeuver3D {
             qi: qi,
             qf: qf,
             rhomin: rhomin,
+            radii: TurnRadii::symmetric(rhomin),
             pitchlims: pitchlims,
+            zlims: None,
             path: Vec::new(),
             length: -1.0
         };
     
-        let safe_radius = (2.0 as f64).sqrt() * maneuver.rhomin;
+        let safe_radius = 2.0_f64.sqrt() * maneuver.rhomin;
         let diff = (qf.x - qi.x, qf.y - qi.y);
         let dist = (diff.0 * diff.0 + diff.1 * diff.1).sqrt();
         if dist < 4.0 * safe_radius {
@@ -125,11 +276,11 @@ impl DubinsManeuver3D {
     
         let qi2d = (maneuver.qi.x, maneuver.qi.y, maneuver.qi.yaw);
         let qf2d = (maneuver.qf.x, maneuver.qf.y, maneuver.qf.yaw);
-        let dlat = DubinsManeuver2D::new(qi2d, qf2d, safe_radius, core::f64::NEG_INFINITY, false);
+        let dlat = DubinsManeuver2D::solve(qi2d, qf2d, safe_radius, core::f64::NEG_INFINITY, false);
     
         let qi3d = (0.0, maneuver.qi.z, maneuver.qi.pitch);
         let qf3d = (dlat.maneuver.length, maneuver.qf.z, maneuver.qf.pitch);
-        let dlon = vertical::get_vertical(qi3d, qf3d, safe_radius, maneuver.pitchlims);
+        let dlon = vertical::get_vertical(qi3d, qf3d, (safe_radius, safe_radius), maneuver.pitchlims);
     
         if dlon.maneuver.case.a == SegmentType::NONE {
             maneuver.length = core::f64::INFINITY;
@@ -141,7 +292,160 @@ impl DubinsManeuver3D {
         return maneuver;
     }
 
-    pub fn compute_sampling(self: &Self, number_of_samples: i32) -> Vec<State> {
+    /// Initial state of the maneuver
+    pub fn start(&self) -> State {
+        return self.qi;
+    }
+
+    /// Final state of the maneuver
+    pub fn goal(&self) -> State {
+        return self.qf;
+    }
+
+    /// Minimum turning radius the maneuver was planned with, the smallest
+    /// of `radii` for asymmetric maneuvers
+    pub fn rhomin(&self) -> f64 {
+        return self.rhomin;
+    }
+
+    /// Turning radius per turn direction the maneuver was planned with
+    pub fn radii(&self) -> TurnRadii {
+        return self.radii;
+    }
+
+    /// Pitch limits the maneuver was planned with
+    pub fn pitchlims(&self) -> (f64, f64) {
+        return self.pitchlims;
+    }
+
+    /// Altitude limits the maneuver was planned with, `None` if unlimited
+    pub fn zlims(&self) -> Option<(f64, f64)> {
+        return self.zlims;
+    }
+
+    /// Total length of the maneuver
+    pub fn length(&self) -> f64 {
+        return self.length;
+    }
+
+    /// Horizontal component in the x-y plane
+    ///
+    /// `None` if only a bound was computed and no path is available.
+    pub fn lateral(&self) -> Option<&DubinsManeuver2D> {
+        return self.path.first();
+    }
+
+    /// Vertical component in the plane of horizontal distance and z,
+    /// the yaw of its states is the pitch of the 3D maneuver
+    ///
+    /// `None` if only a bound was computed and no path is available.
+    pub fn longitudinal(&self) -> Option<&DubinsManeuver2D> {
+        return self.path.get(1);
+    }
+
+    /// Turning radius chosen for the horizontal component
+    pub fn horizontal_radius(&self) -> Option<f64> {
+        return self.lateral().map(|dlat| dlat.rhomin());
+    }
+
+    /// Turning radius chosen for the vertical component
+    pub fn vertical_radius(&self) -> Option<f64> {
+        return self.longitudinal().map(|dlon| dlon.rhomin());
+    }
+
+    /// Segments of the horizontal component, empty if no path is available
+    pub fn lateral_segments(&self) -> Vec<Segment> {
+        return self.lateral().map(|dlat| dlat.segments().to_vec()).unwrap_or_default();
+    }
+
+    /// Segments of the vertical component, empty if no path is available
+    pub fn longitudinal_segments(&self) -> Vec<Segment> {
+        return self.longitudinal().map(|dlon| dlon.segments().to_vec()).unwrap_or_default();
+    }
+
+    /// State at arc length `s` along the maneuver
+    ///
+    /// `s` is clamped to the maneuver, for `s >= length()` the final state is
+    /// returned exactly. Panics if the maneuver holds no path.
+    pub fn state_at(&self, s: f64) -> State {
+        if s >= self.length {
+            return self.qf;
+        }
+        return get_state_at(&self.path[0], &self.path[1], s.max(0.0));
+    }
+
+    /// Rate of change of yaw per unit arc length at `s`, positive when
+    /// turning left
+    ///
+    /// Multiply by the speed to obtain the yaw rate in time. Panics if the
+    /// maneuver holds no path.
+    pub fn yaw_rate_at(&self, s: f64) -> f64 {
+        return get_rates_at(&self.path[0], &self.path[1], s.max(0.0).min(self.length)).0;
+    }
+
+    /// Rate of change of pitch per unit arc length at `s`, positive when
+    /// pulling up
+    ///
+    /// Multiply by the speed to obtain the pitch rate in time. Panics if the
+    /// maneuver holds no path.
+    pub fn pitch_rate_at(&self, s: f64) -> f64 {
+        return get_rates_at(&self.path[0], &self.path[1], s.max(0.0).min(self.length)).1;
+    }
+
+    /// Curvature of the 3D curve at `s`, the inverse of the radius of the
+    /// osculating circle
+    ///
+    /// Panics if the maneuver holds no path.
+    pub fn curvature_at(&self, s: f64) -> f64 {
+        let s = s.max(0.0).min(self.length);
+        let (yaw_rate, pitch_rate) = get_rates_at(&self.path[0], &self.path[1], s);
+        let pitch = self.state_at(s).pitch;
+        return (pitch_rate * pitch_rate + (pitch.cos() * yaw_rate).powi(2)).sqrt();
+    }
+
+    /// Torsion of the 3D curve at `s`, zero where the curve is straight
+    ///
+    /// Within a segment the pitch rate is constant and the yaw rate only
+    /// changes with the pitch, which gives the torsion in closed form.
+    /// Panics if the maneuver holds no path.
+    pub fn torsion_at(&self, s: f64) -> f64 {
+        let s = s.max(0.0).min(self.length);
+        let (yaw_rate, pitch_rate) = get_rates_at(&self.path[0], &self.path[1], s);
+        let pitch = self.state_at(s).pitch;
+        let curvature_squared = pitch_rate * pitch_rate + (pitch.cos() * yaw_rate).powi(2);
+        if curvature_squared == 0.0 {
+            return 0.0;
+        }
+        return pitch.sin() * yaw_rate * (2.0 * pitch_rate * pitch_rate + curvature_squared) / curvature_squared;
+    }
+
+    /// Sorted arc lengths where a lateral or longitudinal segment ends,
+    /// including both ends of the maneuver
+    ///
+    /// Between two consecutive breaks both curvatures are constant and the
+    /// pitch changes linearly. Panics if the maneuver holds no path.
+    pub(crate) fn piece_breaks(&self) -> Vec<f64> {
+        let dlat = &self.path[0];
+        let dlon = &self.path[1];
+
+        let mut breaks = vec![0.0, self.length];
+        let mut offset = 0.0;
+        for segment in dlon.segments().iter().take(2) {
+            offset += segment.length;
+            breaks.push(offset);
+        }
+        let mut offset = 0.0;
+        for segment in dlat.segments().iter().take(2) {
+            offset += segment.length;
+            breaks.push(arc_length_at_horizontal_distance(dlon, offset, self.length));
+        }
+        breaks.retain(|s| *s >= 0.0 && *s <= self.length);
+        breaks.sort_by(|a, b| a.total_cmp(b));
+        return breaks;
+    }
+
+    /// Evenly spaced states along the maneuver, excluding the final state
+    pub fn compute_sampling(&self, number_of_samples: i32) -> Vec<State> {
         let dlat = &self.path[0];
         let dlon = &self.path[1];
     
@@ -149,48 +453,495 @@ impl DubinsManeuver3D {
     
         for sample in 0..number_of_samples {
             let prog: f64 =  dlon.maneuver.length * (sample as f64) / (number_of_samples as f64);
-            // println!("{}", prog);
-            let q_sz = get_coordinates_at(&dlon, prog);
-            let q_xy = get_coordinates_at(&dlat, q_sz.0);
-            points.push(State{x: q_xy.0, y: q_xy.1, z: q_sz.1, yaw: q_xy.2, pitch: q_sz.2});
+            points.push(get_state_at(dlat, dlon, prog));
         }
     
         return points;
     }
+
+    /// Evenly spaced states along the maneuver, starting at the initial
+    /// state and ending exactly at the final state
+    pub fn compute_sampling_inclusive(&self, number_of_samples: i32) -> Vec<State> {
+        if number_of_samples < 2 {
+            return self.compute_sampling(number_of_samples);
+        }
+        let mut points = self.compute_sampling(number_of_samples - 1);
+        points.push(self.qf);
+        return points;
+    }
+
+    /// States spaced `step` apart in arc length, optionally followed by the
+    /// exact final state
+    ///
+    /// Panics if `step` is not positive.
+    pub fn compute_sampling_by_step(&self, step: f64, include_endpoint: bool) -> Vec<State> {
+        let mut samples = self.samples(step);
+        if !include_endpoint {
+            samples.next_back();
+        }
+        return samples.collect();
+    }
+
+    /// Lazily iterate over states spaced `step` apart in arc length, the
+    /// last item is exactly the final state
+    ///
+    /// Panics if `step` is not positive or the maneuver holds no path.
+    pub fn samples(&self, step: f64) -> Samples<'_> {
+        return Samples::new(&self.path[0], &self.path[1], self.qf, self.length, step);
+    }
 }
 
-fn try_to_construct(maneuver: &DubinsManeuver3D, horizontal_radius: f64) -> Vec<DubinsManeuver2D> {
+/// Serialized form of `DubinsManeuver3D`, checked before it is used
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct DubinsManeuver3DRaw {
+    qi: State,
+    qf: State,
+    rhomin: f64,
+    radii: TurnRadii,
+    pitchlims: (f64, f64),
+    zlims: Option<(f64, f64)>,
+    path: Vec<DubinsManeuver2D>,
+    #[serde(deserialize_with = "crate::infinite::deserialize")]
+    length: f64
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<DubinsManeuver3DRaw> for DubinsManeuver3D {
+    type Error = DubinsError;
+
+    fn try_from(raw: DubinsManeuver3DRaw) -> Result<Self, DubinsError> {
+        let radii = raw.radii;
+        for radius in [radii.left, radii.right, radii.up, radii.down] {
+            validate_radius(radius)?;
+        }
+        validate(&raw.qi, &raw.qf, radii.min(), raw.pitchlims)?;
+        if raw.rhomin != radii.min() {
+            return Err(DubinsError::InconsistentData("rhomin is not the smallest turn radius"));
+        }
+        if let Some(zlims) = raw.zlims {
+            validate_altitude_limits(zlims)?;
+            validate_altitude(&raw.qi, Endpoint::Start, zlims)?;
+            validate_altitude(&raw.qf, Endpoint::Goal, zlims)?;
+        }
+
+        match raw.path.as_slice() {
+            [] => {
+                if raw.length.is_nan() || raw.length < 0.0 {
+                    return Err(DubinsError::InconsistentData("length of a bound is negative"));
+                }
+            },
+            [dlat, dlon] => {
+                let lateral = ((raw.qi.x, raw.qi.y, raw.qi.yaw), (raw.qf.x, raw.qf.y, raw.qf.yaw));
+                if !nearly_equal_states(dlat.qi, lateral.0) || !nearly_equal_states(dlat.qf, lateral.1) {
+                    return Err(DubinsError::InconsistentData("lateral component does not connect the endpoints"));
+                }
+                let longitudinal = ((0.0, raw.qi.z, raw.qi.pitch), (dlat.maneuver.length, raw.qf.z, raw.qf.pitch));
+                if !nearly_equal_states(dlon.qi, longitudinal.0) || !nearly_equal_states(dlon.qf, longitudinal.1) {
+                    return Err(DubinsError::InconsistentData("longitudinal component does not connect the endpoints"));
+                }
+                if !raw.length.is_finite() || !nearly_equal(raw.length, dlon.maneuver.length) {
+                    return Err(DubinsError::InconsistentData("length differs from the longitudinal component"));
+                }
+            },
+            _ => return Err(DubinsError::InconsistentData("path needs a lateral and a longitudinal component or none"))
+        }
+
+        return Ok(DubinsManeuver3D {
+            qi: raw.qi,
+            qf: raw.qf,
+            rhomin: raw.rhomin,
+            radii: radii,
+            pitchlims: raw.pitchlims,
+            zlims: raw.zlims,
+            path: raw.path,
+            length: raw.length
+        });
+    }
+}
+
+/// Validation accepts a pitch given as 2pi - x for a descent, the solver
+/// compares it against the pitch limits in (-pi, pi]
+///
+/// Pitches already in range are kept bit for bit so the final state is
+/// returned exactly.
+fn wrap_pitch(q: State) -> State {
+    if q.pitch > -PI && q.pitch <= PI {
+        return q;
+    }
+    return State{pitch: mod_pi(q.pitch), ..q};
+}
+
+/// Whether two values agree up to the rounding of a text round trip
+#[cfg(feature = "serde")]
+pub(crate) fn nearly_equal(a: f64, b: f64) -> bool {
+    return a == b || (a - b).abs() <= 1e-9 * a.abs().max(b.abs()).max(1.0);
+}
+
+#[cfg(feature = "serde")]
+fn nearly_equal_states(a: (f64, f64, f64), b: (f64, f64, f64)) -> bool {
+    return nearly_equal(a.0, b.0) && nearly_equal(a.1, b.1) && nearly_equal(a.2, b.2);
+}
+
+/// Compose the vertical and horizontal components at arc length `s`
+fn get_state_at(dlat: &DubinsManeuver2D, dlon: &DubinsManeuver2D, s: f64) -> State {
+    let q_sz = get_coordinates_at(dlon, s);
+    let q_xy = get_coordinates_at(dlat, q_sz.0);
+    return State{x: q_xy.0, y: q_xy.1, z: q_sz.1, yaw: q_xy.2, pitch: q_sz.2};
+}
+
+/// Arc length at which the horizontal component has advanced `distance`
+fn arc_length_at_horizontal_distance(dlon: &DubinsManeuver2D, distance: f64, length: f64) -> f64 {
+    let mut low = 0.0;
+    let mut high = length;
+    for _ in 0..64 {
+        let mid = 0.5 * (low + high);
+        if get_coordinates_at(dlon, mid).0 < distance {
+            low = mid;
+        }
+        else {
+            high = mid;
+        }
+    }
+    return 0.5 * (low + high);
+}
+
+/// Rates of change of yaw and pitch per unit arc length at `s`
+///
+/// The horizontal curvature is scaled by the cosine of the pitch since only
+/// the horizontal share of the arc length advances the lateral component.
+pub(crate) fn get_rates_at(dlat: &DubinsManeuver2D, dlon: &DubinsManeuver2D, s: f64) -> (f64, f64) {
+    let q_sz = get_coordinates_at(dlon, s);
+    let yaw_rate = get_curvature_at(dlat, q_sz.0) * q_sz.2.cos();
+    let pitch_rate = get_curvature_at(dlon, s);
+    return (yaw_rate, pitch_rate);
+}
+
+/// Try to construct a maneuver whose horizontal radii are the minimum radii
+/// scaled by `b`, leaving the remaining curvature to the vertical plane
+fn try_to_construct(maneuver: &DubinsManeuver3D, normalization: &Normalization, b: f64, lateral: Option<ManeuverCase>) -> Vec<DubinsManeuver2D> {
+    let radii = maneuver.radii;
     let qi2d = (maneuver.qi.x, maneuver.qi.y, maneuver.qi.yaw);
     let qf2d = (maneuver.qf.x, maneuver.qf.y, maneuver.qf.yaw);
 
-    let dlat = DubinsManeuver2D::new(qi2d, qf2d, horizontal_radius, core::f64::NEG_INFINITY, false);
+    let lateral_radii = (b * radii.left, b * radii.right);
+    let dlat = match lateral {
+        None if lateral_radii.0 == lateral_radii.1 => {
+            DubinsManeuver2D::solve_normalized(qi2d, qf2d, normalization, lateral_radii.0, core::f64::NEG_INFINITY, false)
+        },
+        None => DubinsManeuver2D::solve_asymmetric(qi2d, qf2d, lateral_radii, false),
+        Some(case) => {
+            let word = DubinsManeuver2D::solve_all(qi2d, qf2d, lateral_radii, false)
+                .into_iter()
+                .find(|dlat| dlat.case() == case);
+            match word {
+                Some(dlat) => dlat,
+                None => return vec![]
+            }
+        }
+    };
 
     let qi3d = (0.0, maneuver.qi.z, maneuver.qi.pitch);
     let qf3d = (dlat.maneuver.length, maneuver.qf.z, maneuver.qf.pitch);
 
-    let vertical_curvature = (1.0 / maneuver.rhomin / maneuver.rhomin - 1.0 / horizontal_radius / horizontal_radius).sqrt();
-    if vertical_curvature < 1e-5 {
+    let vertical_share = (1.0 - 1.0 / b / b).sqrt();
+    if vertical_share / radii.up.max(radii.down) < 1e-5 {
         return vec![];
     }
 
-    let vertical_radius = 1.0 / vertical_curvature;
+    let vertical_radii = (radii.up / vertical_share, radii.down / vertical_share);
     
-    let dlon = DubinsManeuver2D::new(qi3d, qf3d, vertical_radius, core::f64::NEG_INFINITY, false);
+    let dlon = match maneuver.zlims {
+        None => DubinsManeuver2D::solve_asymmetric(qi3d, qf3d, vertical_radii, false),
+        Some(zlims) => {
+            // Fall back to longer words while the shorter ones leave the
+            // altitude range or the pitch limits
+            let candidates = DubinsManeuver2D::solve_all(qi3d, qf3d, vertical_radii, false);
+            let feasible = candidates.into_iter().find(|dlon| {
+                let (low, high) = vertical::altitude_range(dlon);
+                return low >= zlims.0 && high <= zlims.1 && within_pitch_limits(maneuver, dlon);
+            });
+            match feasible {
+                Some(dlon) => dlon,
+                None => return vec![]
+            }
+        }
+    };
 
-    if dlon.maneuver.case == (ManeuverCase{a: SegmentType::RIGHT, b: SegmentType::LEFT, c: SegmentType::RIGHT}) ||
-        dlon.maneuver.case == (ManeuverCase{a: SegmentType::RIGHT, b: SegmentType::LEFT, c: SegmentType::RIGHT}) {
+    if !within_pitch_limits(maneuver, &dlon) {
         return vec![];
     }
+    return vec![dlat, dlon];
+}
+
+/// Whether a vertical component is a CSC word whose middle segment keeps
+/// within the pitch limits
+fn within_pitch_limits(maneuver: &DubinsManeuver3D, dlon: &DubinsManeuver2D) -> bool {
+    if dlon.maneuver.case == (ManeuverCase{a: SegmentType::RIGHT, b: SegmentType::LEFT, c: SegmentType::RIGHT}) ||
+        dlon.maneuver.case == (ManeuverCase{a: SegmentType::LEFT, b: SegmentType::RIGHT, c: SegmentType::LEFT}) {
+        return false;
+    }
 
     if dlon.maneuver.case.a == SegmentType::RIGHT {
-        if maneuver.qi.pitch - dlon.maneuver.t < maneuver.pitchlims.0 {
-            return vec![];
+        return maneuver.qi.pitch - dlon.maneuver.t >= maneuver.pitchlims.0;
+    }
+    return maneuver.qi.pitch + dlon.maneuver.t <= maneuver.pitchlims.1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tangent(maneuver: &DubinsManeuver3D, s: f64) -> (f64, f64, f64) {
+        let q = maneuver.state_at(s);
+        return (q.pitch.cos() * q.yaw.cos(), q.pitch.cos() * q.yaw.sin(), q.pitch.sin());
+    }
+
+    fn cross(a: (f64, f64, f64), b: (f64, f64, f64)) -> (f64, f64, f64) {
+        return (a.1 * b.2 - a.2 * b.1, a.2 * b.0 - a.0 * b.2, a.0 * b.1 - a.1 * b.0);
+    }
+
+    #[test]
+    fn rates_match_finite_differences() {
+        let qi = State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+        let qf = State{x: 120.0, y: 90.0, z: 60.0, yaw: 2.5, pitch: -0.1};
+        let maneuver = DubinsManeuver3D::new(qi, qf, 40.0, (-0.4, 0.4));
+        let breaks = maneuver.piece_breaks();
+        let h = 1e-3;
+        let mut checked = 0;
+        for piece in breaks.windows(2) {
+            if piece[1] - piece[0] < 10.0 * h {
+                continue;
+            }
+            for fraction in [0.25, 0.5, 0.75] {
+                let s = piece[0] + fraction * (piece[1] - piece[0]);
+                let (before, after) = (maneuver.state_at(s - h), maneuver.state_at(s + h));
+                let yaw_rate = mod_pi(after.yaw - before.yaw) / (2.0 * h);
+                let pitch_rate = mod_pi(after.pitch - before.pitch) / (2.0 * h);
+                assert!((maneuver.yaw_rate_at(s) - yaw_rate).abs() < 1e-6);
+                assert!((maneuver.pitch_rate_at(s) - pitch_rate).abs() < 1e-6);
+
+                // Frenet-Serret from the tangent and its derivatives
+                let (t0, t1, t2) = (tangent(&maneuver, s - h), tangent(&maneuver, s), tangent(&maneuver, s + h));
+                let d1 = ((t2.0 - t0.0) / (2.0 * h), (t2.1 - t0.1) / (2.0 * h), (t2.2 - t0.2) / (2.0 * h));
+                let d2 = (
+                    (t2.0 - 2.0 * t1.0 + t0.0) / (h * h),
+                    (t2.1 - 2.0 * t1.1 + t0.1) / (h * h),
+                    (t2.2 - 2.0 * t1.2 + t0.2) / (h * h)
+                );
+                let curvature = (d1.0 * d1.0 + d1.1 * d1.1 + d1.2 * d1.2).sqrt();
+                assert!((maneuver.curvature_at(s) - curvature).abs() < 1e-6);
+                if curvature > 1e-6 {
+                    let b = cross(t1, d1);
+                    let torsion = (b.0 * d2.0 + b.1 * d2.1 + b.2 * d2.2) / (curvature * curvature);
+                    assert!((maneuver.torsion_at(s) - torsion).abs() < 1e-4, "{} {}", maneuver.torsion_at(s), torsion);
+                }
+                checked += 1;
+            }
         }
+        assert!(checked >= 6);
     }
-    else {
-        if maneuver.qi.pitch + dlon.maneuver.t > maneuver.pitchlims.1 {
-            return vec![];
+
+    #[test]
+    fn level_turn_and_straight_climb_in_closed_form() {
+        let qi = State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+        let turn = DubinsManeuver3D::new(qi, State{x: 0.0, y: 80.0, z: 0.0, yaw: PI, pitch: 0.0}, 40.0, (-0.3, 0.3));
+        let s = 0.5 * turn.length();
+        assert!((turn.curvature_at(s) - 1.0 / turn.lateral().unwrap().rhomin()).abs() < 1e-9);
+        assert!(turn.torsion_at(s).abs() < 1e-12);
+        assert!(turn.pitch_rate_at(s).abs() < 1e-12);
+
+        let climb = State{pitch: 0.2, ..qi};
+        let goal = State{x: 400.0 * 0.2f64.cos(), y: 0.0, z: 400.0 * 0.2f64.sin(), yaw: 0.0, pitch: 0.2};
+        let straight = DubinsManeuver3D::new(climb, goal, 40.0, (-0.3, 0.3));
+        assert!((straight.length() - 400.0).abs() < 1e-6);
+        for s in [10.0, 200.0, 390.0] {
+            assert!(straight.curvature_at(s).abs() < 1e-9);
+            assert!(straight.torsion_at(s).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn altitude_limits_are_kept() {
+        let qi = State{x: 0.0, y: 0.0, z: 50.0, yaw: 0.0, pitch: 0.2};
+        let qf = State{x: 60.0, y: 80.0, z: 60.0, yaw: 3.0, pitch: 0.2};
+        let zlims = (45.0, 70.0);
+        let maneuver = DubinsManeuver3D::new_with_zlims(qi, qf, 40.0, (-0.3, 0.3), zlims);
+        for q in maneuver.compute_sampling(500) {
+            assert!(q.z >= zlims.0 - 1e-6 && q.z <= zlims.1 + 1e-6);
+        }
+    }
+
+    #[test]
+    fn unreachable_altitude_limits_fail() {
+        // Leaving at full climb needs more headroom than the ceiling allows
+        let qi = State{x: 0.0, y: 0.0, z: 50.0, yaw: 0.0, pitch: 0.3};
+        let qf = State{x: 200.0, y: 0.0, z: 50.0, yaw: 0.0, pitch: 0.0};
+        let result = DubinsManeuver3D::try_new_with_zlims(qi, qf, 40.0, (-0.3, 0.3), (0.0, 50.001));
+        assert_eq!(result.unwrap_err(), DubinsError::SearchFailed);
+    }
+
+    fn goals() -> Vec<State> {
+        return vec![
+            State{x: 120.0, y: 90.0, z: 60.0, yaw: 2.5, pitch: -0.1},
+            State{x: -50.0, y: 30.0, z: -80.0, yaw: -1.0, pitch: 0.2},
+            State{x: 20.0, y: -150.0, z: 200.0, yaw: 0.5, pitch: 0.0},
+            State{x: 300.0, y: 10.0, z: 5.0, yaw: 0.0, pitch: 0.0}
+        ];
+    }
+
+    #[test]
+    fn segments_chain_from_start_to_goal() {
+        let qi = State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+        for qf in goals() {
+            let maneuver = DubinsManeuver3D::new(qi, qf, 40.0, (-0.4, 0.4));
+            let lateral = maneuver.lateral_segments();
+            let longitudinal = maneuver.longitudinal_segments();
+            assert_eq!(lateral.len(), 3);
+            assert_eq!(longitudinal.len(), 3);
+            for segments in [&lateral, &longitudinal] {
+                for pair in segments.windows(2) {
+                    assert_eq!(pair[0].end, pair[1].start);
+                }
+            }
+
+            // Lateral segments run in the x-y plane
+            let (start, end) = (lateral[0].start, lateral[2].end);
+            assert!(start.x == qi.x && start.y == qi.y && start.yaw == qi.yaw);
+            assert!((end.x - qf.x).abs() < 1e-6 && (end.y - qf.y).abs() < 1e-6 && mod_pi(end.yaw - qf.yaw).abs() < 1e-6);
+
+            // Longitudinal segments run over horizontal distance and z
+            let horizontal: f64 = lateral.iter().map(|segment| segment.length).sum();
+            let (start, end) = (longitudinal[0].start, longitudinal[2].end);
+            assert!(start.x == 0.0 && start.y == qi.z && start.yaw == qi.pitch);
+            assert!((end.x - horizontal).abs() < 1e-6 && (end.y - qf.z).abs() < 1e-6 && mod_pi(end.yaw - qf.pitch).abs() < 1e-6);
+            let total: f64 = longitudinal.iter().map(|segment| segment.length).sum();
+            assert!((total - maneuver.length()).abs() < 1e-9 * maneuver.length());
+
+            for (segments, radius) in [(&lateral, maneuver.horizontal_radius()), (&longitudinal, maneuver.vertical_radius())] {
+                for segment in segments.iter() {
+                    if segment.kind == SegmentType::STRAIGHT {
+                        assert!(segment.radius.is_infinite() && segment.center.is_none());
+                    } else {
+                        assert_eq!(Some(segment.radius), radius);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn state_at_ends_are_start_and_goal() {
+        let qi = State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+        for qf in goals() {
+            let maneuver = DubinsManeuver3D::new(qi, qf, 40.0, (-0.4, 0.4));
+            assert_eq!(maneuver.state_at(0.0), qi);
+            assert_eq!(maneuver.state_at(maneuver.length()), qf);
+            assert_eq!(maneuver.state_at(-1.0), qi);
+            assert_eq!(maneuver.state_at(maneuver.length() + 1.0), qf);
+        }
+    }
+
+    #[test]
+    fn sampling_by_step_counts() {
+        let qi = State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+        for qf in goals() {
+            let maneuver = DubinsManeuver3D::new(qi, qf, 40.0, (-0.4, 0.4));
+            for step in [0.7, 5.0, 33.3, 1000.0] {
+                let count = (maneuver.length() / step).ceil() as usize;
+                let open = maneuver.compute_sampling_by_step(step, false);
+                let closed = maneuver.compute_sampling_by_step(step, true);
+                assert_eq!(open.len(), count);
+                assert_eq!(closed.len(), count + 1);
+                assert_eq!(open[0], qi);
+                assert_eq!(closed[count], qf);
+                assert_eq!(&closed[..count], &open[..]);
+                for (i, q) in open.iter().enumerate() {
+                    assert_eq!(*q, maneuver.state_at(step * i as f64));
+                }
+            }
+        }
+
+        // A step dividing the length evenly ends on the goal without repeating it
+        let straight = DubinsManeuver3D::new(qi, State{x: 100.0, ..qi}, 40.0, (-0.4, 0.4));
+        let samples = straight.compute_sampling_by_step(25.0, true);
+        assert_eq!(samples.len(), 5);
+        assert!((samples[3].x - 75.0).abs() < 1e-9);
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    fn maneuver() -> DubinsManeuver3D {
+        let qi = State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+        let qf = State{x: 200.0, y: 100.0, z: 40.0, yaw: 2.0, pitch: 0.1};
+        return DubinsManeuver3D::new(qi, qf, 40.0, (-0.3, 0.3));
+    }
+
+    #[test]
+    fn round_trip() {
+        let maneuver = maneuver();
+        let json = serde_json::to_string(&maneuver).unwrap();
+        let restored: DubinsManeuver3D = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.length(), maneuver.length());
+        let (a, b) = (restored.state_at(50.0), maneuver.state_at(50.0));
+        assert!((a.x - b.x).abs() + (a.y - b.y).abs() + (a.z - b.z).abs() < 1e-9);
+
+        let bound = DubinsManeuver3D::get_lower_bound(maneuver.start(), maneuver.goal(), 40.0, (-0.3, 0.3)).unwrap();
+        let json = serde_json::to_string(&bound).unwrap();
+        assert!(serde_json::from_str::<DubinsManeuver3D>(&json).is_ok());
+    }
+
+    #[test]
+    fn rejects_inconsistent_maneuvers() {
+        let mut value = serde_json::to_value(maneuver()).unwrap();
+        value["path"].as_array_mut().unwrap().pop();
+        assert!(serde_json::from_value::<DubinsManeuver3D>(value).is_err());
+
+        let mut value = serde_json::to_value(maneuver()).unwrap();
+        value["length"] = serde_json::json!(1.0);
+        assert!(serde_json::from_value::<DubinsManeuver3D>(value).is_err());
+
+        let mut value = serde_json::to_value(maneuver()).unwrap();
+        value["rhomin"] = serde_json::json!(-1.0);
+        assert!(serde_json::from_value::<DubinsManeuver3D>(value).is_err());
+    }
+
+    #[test]
+    fn upper_bound_without_path_round_trips() {
+        let qi = State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+        let qf = State{x: 50.0, y: 20.0, z: 10.0, yaw: 1.0, pitch: 0.0};
+        let bound = DubinsManeuver3D::get_upper_bound(qi, qf, 40.0, (-0.3, 0.3)).unwrap();
+        assert!(bound.length().is_infinite());
+        let json = serde_json::to_string(&bound).unwrap();
+        let restored: DubinsManeuver3D = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.length(), core::f64::INFINITY);
+        assert!(restored.lateral().is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_components() {
+        let corruptions = [
+            ("rhomin", serde_json::json!(-40.0)),
+            ("radii", serde_json::json!([-40.0, -40.0])),
+            ("radii", serde_json::json!([40.0, 0.0]))
+        ];
+        for component in 0..2 {
+            for (field, value) in corruptions.iter() {
+                let mut json = serde_json::to_value(maneuver()).unwrap();
+                json["path"][component][*field] = value.clone();
+                assert!(serde_json::from_value::<DubinsManeuver3D>(json).is_err(), "{} {}", field, value);
+            }
+            for parameter in ["t", "p", "q"] {
+                let mut json = serde_json::to_value(maneuver()).unwrap();
+                json["path"][component]["maneuver"][parameter] = serde_json::json!(-0.5);
+                assert!(serde_json::from_value::<DubinsManeuver3D>(json).is_err(), "{}", parameter);
+                let mut json = serde_json::to_value(maneuver()).unwrap();
+                json["path"][component]["maneuver"][parameter] = serde_json::Value::Null;
+                assert!(serde_json::from_value::<DubinsManeuver3D>(json).is_err(), "{}", parameter);
+            }
         }
     }
-    return vec![dlat, dlon];
 }
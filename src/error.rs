@@ -0,0 +1,111 @@
+use core::fmt;
+
+/// Identifies the state of a planning problem an error refers to
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Endpoint {
+    Start,
+    Goal
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Endpoint::Start => write!(f, "start"),
+            Endpoint::Goal => write!(f, "goal"),
+        }
+    }
+}
+
+/// Reasons a Dubins maneuver cannot be constructed
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DubinsError {
+    /// Pitch limits are not an increasing range strictly inside (-pi/2, pi/2)
+    InfeasiblePitchLimits(f64, f64),
+    /// Altitude limits are not an increasing range
+    InfeasibleAltitudeLimits(f64, f64),
+    /// Altitude of the start or goal state lies outside of the altitude limits
+    AltitudeOutOfLimits(Endpoint, f64),
+    /// Minimum turning radius is zero, negative or not finite
+    NonPositiveRadius(f64),
+    /// Named field of the start or goal state is NaN or infinite
+    NonFiniteState(Endpoint, &'static str),
+    /// Pitch of the start or goal state lies outside of the pitch limits
+    PitchOutOfLimits(Endpoint, f64),
+    /// Radius search did not find a feasible maneuver
+    SearchFailed,
+    /// A path needs at least two waypoints, holds the number given
+    TooFewWaypoints(usize),
+    /// A tour needs at least one target
+    NoTargets,
+    /// Leg at the given index does not start at the goal of the previous leg
+    DiscontinuousLegs(usize),
+    /// Leg at the given index holds only a length bound and no path
+    LegWithoutPath(usize),
+    /// Vehicle constraints cannot be converted into planning limits
+    InvalidVehicleConstraint(&'static str),
+    /// Speed profile cannot be flown along the maneuver
+    InvalidSpeedProfile(&'static str),
+    /// Planner did not find a collision-free path within its budget
+    NoPathFound,
+    /// Collision check resolution is zero, negative or not finite
+    NonPositiveResolution(f64),
+    /// Heightmap grid is malformed or inconsistent
+    InvalidHeightmap(&'static str),
+    /// Waypoint at the given index is too close to or outside of the terrain
+    InsufficientClearance(usize),
+    /// Minimum clearance above the terrain is negative or not finite
+    InvalidClearance(f64),
+    /// Airspeed or wind cannot be flown in
+    InvalidWind(&'static str),
+    /// Deserialized value contradicts itself or its constructor's checks
+    InconsistentData(&'static str)
+}
+
+impl fmt::Display for DubinsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DubinsError::InfeasiblePitchLimits(min, max) =>
+                write!(f, "infeasible pitch limits ({}, {})", min, max),
+            DubinsError::InfeasibleAltitudeLimits(min, max) =>
+                write!(f, "infeasible altitude limits ({}, {})", min, max),
+            DubinsError::AltitudeOutOfLimits(endpoint, z) =>
+                write!(f, "{} altitude {} is outside of the altitude limits", endpoint, z),
+            DubinsError::NonPositiveRadius(rhomin) =>
+                write!(f, "minimum turning radius must be positive, got {}", rhomin),
+            DubinsError::NonFiniteState(endpoint, field) =>
+                write!(f, "{} state has a non-finite {}", endpoint, field),
+            DubinsError::PitchOutOfLimits(endpoint, pitch) =>
+                write!(f, "{} pitch {} is outside of the pitch limits", endpoint, pitch),
+            DubinsError::SearchFailed =>
+                write!(f, "search for a feasible maneuver did not converge"),
+            DubinsError::TooFewWaypoints(count) =>
+                write!(f, "a path needs at least two waypoints, got {}", count),
+            DubinsError::NoTargets =>
+                write!(f, "a tour needs at least one target"),
+            DubinsError::DiscontinuousLegs(index) =>
+                write!(f, "leg {} does not start at the goal of the previous leg", index),
+            DubinsError::LegWithoutPath(index) =>
+                write!(f, "leg {} holds only a length bound and no path", index),
+            DubinsError::InvalidVehicleConstraint(reason) =>
+                write!(f, "invalid vehicle constraint: {}", reason),
+            DubinsError::InvalidSpeedProfile(reason) =>
+                write!(f, "invalid speed profile: {}", reason),
+            DubinsError::NoPathFound =>
+                write!(f, "planner did not find a collision-free path"),
+            DubinsError::NonPositiveResolution(resolution) =>
+                write!(f, "collision check resolution must be positive, got {}", resolution),
+            DubinsError::InvalidHeightmap(reason) =>
+                write!(f, "invalid heightmap: {}", reason),
+            DubinsError::InsufficientClearance(index) =>
+                write!(f, "waypoint {} does not keep the minimum clearance above the terrain", index),
+            DubinsError::InvalidClearance(clearance) =>
+                write!(f, "minimum clearance must be finite and non-negative, got {}", clearance),
+            DubinsError::InvalidWind(reason) =>
+                write!(f, "invalid wind: {}", reason),
+            DubinsError::InconsistentData(reason) =>
+                write!(f, "inconsistent data: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for DubinsError {}
@@ -0,0 +1,160 @@
+use crate::{PI, State, mod2pi};
+use crate::error::DubinsError;
+use crate::dubins3d::{DubinsManeuver3D, TurnRadii};
+
+/// Convention of the world frame states and orientations are expressed in
+///
+/// The solver works in ENU: z points up and yaw is counterclockwise from the
+/// x axis. In both frames pitch is positive when climbing.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Frame {
+    /// x east, y north, z up, body axes forward-left-up
+    #[default]
+    ENU,
+    /// x north, y east, z down, yaw clockwise from north, body axes
+    /// forward-right-down
+    NED
+}
+
+impl Frame {
+    /// Express an ENU position or direction in this frame
+    pub fn vector_from_enu(&self, v: (f64, f64, f64)) -> (f64, f64, f64) {
+        return match self {
+            Frame::ENU => v,
+            Frame::NED => (v.1, v.0, -v.2)
+        };
+    }
+
+    /// Express a position or direction given in this frame in ENU
+    pub fn vector_to_enu(&self, v: (f64, f64, f64)) -> (f64, f64, f64) {
+        // Swapping the horizontal axes and flipping z is its own inverse
+        return self.vector_from_enu(v);
+    }
+
+    /// Express an ENU state in this frame
+    pub fn state_from_enu(&self, q: &State) -> State {
+        return match self {
+            Frame::ENU => *q,
+            Frame::NED => State{x: q.y, y: q.x, z: -q.z, yaw: mod2pi(PI / 2.0 - q.yaw), pitch: q.pitch}
+        };
+    }
+
+    /// Express a state given in this frame in ENU
+    pub fn state_to_enu(&self, q: &State) -> State {
+        return self.state_from_enu(q);
+    }
+
+    /// Express altitude limits given in this frame in ENU
+    ///
+    /// NED limits bound z pointing down, so they are negated and swapped.
+    pub fn altitude_limits_to_enu(&self, zlims: (f64, f64)) -> (f64, f64) {
+        return match self {
+            Frame::ENU => zlims,
+            Frame::NED => (-zlims.1, -zlims.0)
+        };
+    }
+
+    /// Yaw, pitch and roll in this frame for the z-y-x rotation sequence,
+    /// from ENU yaw and pitch and a roll that is positive to the left
+    pub(crate) fn euler_from_enu(&self, yaw: f64, pitch: f64, roll: f64) -> (f64, f64, f64) {
+        return match self {
+            Frame::ENU => (yaw, -pitch, -roll),
+            Frame::NED => (PI / 2.0 - yaw, pitch, -roll)
+        };
+    }
+}
+
+impl DubinsManeuver3D {
+    /// Create a new Dubins maneuver between states given in `frame`,
+    /// returning an error if no maneuver can be constructed
+    pub fn try_new_in(frame: Frame, qi: State, qf: State, rhomin: f64, pitchlims: (f64, f64)) -> Result<DubinsManeuver3D, DubinsError> {
+        return DubinsManeuver3D::try_new(frame.state_to_enu(&qi), frame.state_to_enu(&qf), rhomin, pitchlims);
+    }
+
+    /// Create a new Dubins maneuver with a separate turning radius per turn
+    /// direction between states given in `frame`, see `try_new_asymmetric`
+    ///
+    /// Turn directions are relative to the vehicle and mean the same in
+    /// every frame.
+    pub fn try_new_asymmetric_in(frame: Frame, qi: State, qf: State, radii: TurnRadii, pitchlims: (f64, f64)) -> Result<DubinsManeuver3D, DubinsError> {
+        return DubinsManeuver3D::try_new_asymmetric(frame.state_to_enu(&qi), frame.state_to_enu(&qf), radii, pitchlims);
+    }
+
+    /// Create a new Dubins maneuver between states given in `frame` that stays
+    /// between the z limits `zlims` of that frame, see `try_new_with_zlims`
+    pub fn try_new_with_zlims_in(frame: Frame, qi: State, qf: State, rhomin: f64, pitchlims: (f64, f64), zlims: (f64, f64)) -> Result<DubinsManeuver3D, DubinsError> {
+        let zlims = frame.altitude_limits_to_enu(zlims);
+        return DubinsManeuver3D::try_new_with_zlims(frame.state_to_enu(&qi), frame.state_to_enu(&qf), rhomin, pitchlims, zlims);
+    }
+
+    /// State at arc length `s` expressed in `frame`, see `state_at`
+    pub fn state_at_in(&self, s: f64, frame: Frame) -> State {
+        return frame.state_from_enu(&self.state_at(s));
+    }
+
+    /// Lazily iterate over states expressed in `frame`, see `samples`
+    pub fn samples_in(&self, step: f64, frame: Frame) -> impl DoubleEndedIterator<Item = State> + ExactSizeIterator + '_ {
+        return self.samples(step).map(move |q| frame.state_from_enu(&q));
+    }
+
+    /// States spaced `step` apart in arc length expressed in `frame`, see
+    /// `compute_sampling_by_step`
+    pub fn compute_sampling_by_step_in(&self, step: f64, include_endpoint: bool, frame: Frame) -> Vec<State> {
+        let mut samples = self.samples_in(step, frame);
+        if !include_endpoint {
+            samples.next_back();
+        }
+        return samples.collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ned_states() -> (State, State) {
+        let qi = State{x: 0.0, y: 0.0, z: -100.0, yaw: 0.3, pitch: 0.0};
+        let qf = State{x: 150.0, y: -80.0, z: -160.0, yaw: 2.0, pitch: 0.1};
+        return (qi, qf);
+    }
+
+    #[test]
+    fn state_conversions_round_trip() {
+        let q = State{x: 12.0, y: -7.0, z: 30.0, yaw: 5.5, pitch: -0.2};
+        for frame in [Frame::ENU, Frame::NED] {
+            let back = frame.state_to_enu(&frame.state_from_enu(&q));
+            assert!((back.x - q.x).abs() < 1e-12 && (back.y - q.y).abs() < 1e-12 && (back.z - q.z).abs() < 1e-12);
+            assert!((back.yaw - q.yaw).abs() < 1e-12 && (back.pitch - q.pitch).abs() < 1e-12);
+            let back = frame.state_from_enu(&frame.state_to_enu(&q));
+            assert!((back.x - q.x).abs() < 1e-12 && (back.yaw - q.yaw).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn asymmetric_maneuver_in_ned_ends_at_the_goal() {
+        let (qi, qf) = ned_states();
+        let radii = TurnRadii{left: 30.0, right: 50.0, up: 60.0, down: 35.0};
+        let maneuver = DubinsManeuver3D::try_new_asymmetric_in(Frame::NED, qi, qf, radii, (-0.4, 0.4)).unwrap();
+        let enu = DubinsManeuver3D::try_new_asymmetric(Frame::NED.state_to_enu(&qi), Frame::NED.state_to_enu(&qf), radii, (-0.4, 0.4)).unwrap();
+        assert_eq!(maneuver.length(), enu.length());
+        let end = maneuver.state_at_in(maneuver.length(), Frame::NED);
+        assert!((end.x - qf.x).abs() < 1e-6 && (end.y - qf.y).abs() < 1e-6 && (end.z - qf.z).abs() < 1e-6);
+    }
+
+    #[test]
+    fn zlims_in_ned_bound_the_depth() {
+        let (qi, qf) = ned_states();
+        // z down between 90 m and 170 m above the origin
+        let zlims = (-170.0, -90.0);
+        assert_eq!(Frame::NED.altitude_limits_to_enu(zlims), (90.0, 170.0));
+        let maneuver = DubinsManeuver3D::try_new_with_zlims_in(Frame::NED, qi, qf, 40.0, (-0.4, 0.4), zlims).unwrap();
+        for q in maneuver.samples_in(1.0, Frame::NED) {
+            assert!(q.z >= zlims.0 - 1e-6 && q.z <= zlims.1 + 1e-6, "{:?}", q);
+        }
+
+        // The goal lies above the ceiling once the limits are read as NED
+        let result = DubinsManeuver3D::try_new_with_zlims_in(Frame::NED, qi, qf, 40.0, (-0.4, 0.4), (-150.0, -90.0));
+        assert!(matches!(result, Err(DubinsError::AltitudeOutOfLimits(..))), "{:?}", result);
+    }
+}
@@ -0,0 +1,353 @@
+use crate::{State, mod2pi};
+use crate::error::DubinsError;
+use crate::frame::Frame;
+use crate::dubins3d::DubinsManeuver3D;
+
+/// Semi-major axis of the WGS84 ellipsoid in meters
+pub const WGS84_A: f64 = 6378137.0;
+/// Flattening of the WGS84 ellipsoid
+pub const WGS84_F: f64 = 1.0 / 298.257223563;
+
+const WGS84_E2: f64 = WGS84_F * (2.0 - WGS84_F);
+
+/// Position on the WGS84 ellipsoid, angles in radians and the altitude in
+/// meters above the ellipsoid
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Geodetic {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude: f64
+}
+
+impl Geodetic {
+    /// Position from latitude and longitude in degrees as found in mission files
+    pub fn from_degrees(latitude: f64, longitude: f64, altitude: f64) -> Self {
+        return Geodetic{latitude: latitude.to_radians(), longitude: longitude.to_radians(), altitude: altitude};
+    }
+
+    /// Latitude and longitude in degrees
+    pub fn to_degrees(&self) -> (f64, f64) {
+        return (self.latitude.to_degrees(), self.longitude.to_degrees());
+    }
+
+    /// Earth-centered, earth-fixed coordinates in meters
+    pub fn to_ecef(&self) -> (f64, f64, f64) {
+        let (sin_lat, cos_lat) = self.latitude.sin_cos();
+        let (sin_lon, cos_lon) = self.longitude.sin_cos();
+        let n = WGS84_A / (1.0 - WGS84_E2 * sin_lat * sin_lat).sqrt();
+        return (
+            (n + self.altitude) * cos_lat * cos_lon,
+            (n + self.altitude) * cos_lat * sin_lon,
+            (n * (1.0 - WGS84_E2) + self.altitude) * sin_lat
+        );
+    }
+
+    /// Position of earth-centered, earth-fixed coordinates in meters
+    pub fn from_ecef(ecef: (f64, f64, f64)) -> Self {
+        let (x, y, z) = ecef;
+        let p = (x * x + y * y).sqrt();
+        let longitude = y.atan2(x);
+        let mut latitude = z.atan2(p * (1.0 - WGS84_E2));
+        let mut altitude = 0.0;
+        for _ in 0..20 {
+            let (sin_lat, cos_lat) = latitude.sin_cos();
+            let n = WGS84_A / (1.0 - WGS84_E2 * sin_lat * sin_lat).sqrt();
+            // Take the altitude from the better conditioned axis
+            altitude = if cos_lat.abs() > sin_lat.abs() {
+                p / cos_lat - n
+            }
+            else {
+                z / sin_lat - n * (1.0 - WGS84_E2)
+            };
+            let next = z.atan2(p * (1.0 - WGS84_E2 * n / (n + altitude)));
+            let converged = (next - latitude).abs() < 1e-15;
+            latitude = next;
+            if converged {
+                break;
+            }
+        }
+        return Geodetic{latitude: latitude, longitude: longitude, altitude: altitude};
+    }
+
+    /// East, north and up unit vectors of the tangent plane in ECEF
+    fn enu_axes(&self) -> [(f64, f64, f64); 3] {
+        let (sin_lat, cos_lat) = self.latitude.sin_cos();
+        let (sin_lon, cos_lon) = self.longitude.sin_cos();
+        return [
+            (-sin_lon, cos_lon, 0.0),
+            (-sin_lat * cos_lon, -sin_lat * sin_lon, cos_lat),
+            (cos_lat * cos_lon, cos_lat * sin_lon, sin_lat)
+        ];
+    }
+}
+
+/// Geodetic position with the direction of travel, `heading` in radians
+/// clockwise from true north and `pitch` positive when climbing
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeodeticState {
+    pub position: Geodetic,
+    pub heading: f64,
+    pub pitch: f64
+}
+
+/// Local frame around a reference origin: east and north in the tangent
+/// plane at the origin, up as altitude above the origin
+///
+/// A position maps to the point where the ellipsoid normal through it crosses
+/// the plane, and to its altitude. Level flight in the frame therefore keeps
+/// its altitude above the ellipsoid and altitude limits hold relative to the
+/// ellipsoid however far the maneuver goes, and positions and directions are
+/// converted exactly in both directions. Distances in the plane exceed those
+/// on the ground by a factor of about `1 + (d / R)^2` at distance `d` from the
+/// origin, 0.006% at 50 km, and fall short of those flown at a height `h`
+/// above the origin by `h / R`, so turn radii and pitch limits hold to that
+/// accuracy. Headings are corrected for the convergence of meridians away
+/// from the origin.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocalTangentPlane {
+    origin: Geodetic,
+    origin_ecef: (f64, f64, f64),
+    axes: [(f64, f64, f64); 3]
+}
+
+impl LocalTangentPlane {
+    /// Tangent plane at `origin`
+    pub fn new(origin: Geodetic) -> Self {
+        return LocalTangentPlane{origin: origin, origin_ecef: origin.to_ecef(), axes: origin.enu_axes()};
+    }
+
+    /// Reference origin of the plane
+    pub fn origin(&self) -> Geodetic {
+        return self.origin;
+    }
+
+    /// Local coordinates of a geodetic position in `frame`
+    pub fn to_local(&self, position: &Geodetic, frame: Frame) -> (f64, f64, f64) {
+        let (crossing, _) = self.crossing(position);
+        let d = sub(crossing, self.origin_ecef);
+        let enu = (dot(d, self.axes[0]), dot(d, self.axes[1]), position.altitude - self.origin.altitude);
+        return frame.vector_from_enu(enu);
+    }
+
+    /// Geodetic position of local coordinates given in `frame`
+    pub fn to_geodetic(&self, local: (f64, f64, f64), frame: Frame) -> Geodetic {
+        let enu = frame.vector_to_enu(local);
+        let crossing = combine(&self.axes, (enu.0, enu.1, 0.0));
+        let footprint = Geodetic::from_ecef(add(self.origin_ecef, crossing));
+        return Geodetic {
+            latitude: footprint.latitude,
+            longitude: footprint.longitude,
+            altitude: self.origin.altitude + enu.2
+        };
+    }
+
+    /// ENU state in the local frame, as expected by the solver
+    pub fn state_from_geodetic(&self, q: &GeodeticState) -> State {
+        let axes = q.position.enu_axes();
+        let (sin_heading, cos_heading) = q.heading.sin_cos();
+        let (sin_pitch, cos_pitch) = q.pitch.sin_cos();
+        let direction = combine(&axes, (cos_pitch * sin_heading, cos_pitch * cos_heading, sin_pitch));
+        let local = self.velocity_to_local(&q.position, direction);
+
+        let position = self.to_local(&q.position, Frame::ENU);
+        return State {
+            x: position.0,
+            y: position.1,
+            z: position.2,
+            yaw: mod2pi(local.1.atan2(local.0)),
+            pitch: local.2.atan2(local.0.hypot(local.1))
+        };
+    }
+
+    /// Geodetic state of an ENU state in the local frame
+    pub fn state_to_geodetic(&self, q: &State) -> GeodeticState {
+        let position = self.to_geodetic((q.x, q.y, q.z), Frame::ENU);
+        let local = (q.pitch.cos() * q.yaw.cos(), q.pitch.cos() * q.yaw.sin(), q.pitch.sin());
+        let direction = self.velocity_to_ecef(&position, local);
+        let axes = position.enu_axes();
+        let east = dot(direction, axes[0]);
+        let north = dot(direction, axes[1]);
+        let up = dot(direction, axes[2]);
+        return GeodeticState {
+            position: position,
+            heading: mod2pi(east.atan2(north)),
+            pitch: up.atan2(east.hypot(north))
+        };
+    }
+
+    /// Point where the ellipsoid normal through `position` crosses the plane
+    /// in ECEF, with the signed distance along the normal from the position
+    fn crossing(&self, position: &Geodetic) -> ((f64, f64, f64), f64) {
+        let ecef = position.to_ecef();
+        let up = position.enu_axes()[2];
+        let offset = -dot(sub(ecef, self.origin_ecef), self.axes[2]) / dot(up, self.axes[2]);
+        return (add(ecef, scale(up, offset)), offset);
+    }
+
+    /// Ratio of the horizontal speed of the crossing point to the horizontal
+    /// speed at `position`, the normals spread out above the ellipsoid
+    fn crossing_speed(&self, position: &Geodetic) -> f64 {
+        let (_, offset) = self.crossing(position);
+        let sin_lat = position.latitude.sin();
+        let n = WGS84_A / (1.0 - WGS84_E2 * sin_lat * sin_lat).sqrt();
+        return 1.0 + offset / (n + position.altitude);
+    }
+
+    /// Local ENU velocity of an ECEF velocity at `position`
+    fn velocity_to_local(&self, position: &Geodetic, v: (f64, f64, f64)) -> (f64, f64, f64) {
+        let up = position.enu_axes()[2];
+        let climb = dot(v, up);
+        let horizontal = scale(sub(v, scale(up, climb)), self.crossing_speed(position));
+        // Slide along the normal back into the plane
+        let w = sub(horizontal, scale(up, dot(horizontal, self.axes[2]) / dot(up, self.axes[2])));
+        return (dot(w, self.axes[0]), dot(w, self.axes[1]), climb);
+    }
+
+    /// ECEF velocity at `position` of a local ENU velocity, the inverse of
+    /// `velocity_to_local`
+    fn velocity_to_ecef(&self, position: &Geodetic, local: (f64, f64, f64)) -> (f64, f64, f64) {
+        let up = position.enu_axes()[2];
+        let w = combine(&self.axes, (local.0, local.1, 0.0));
+        let horizontal = scale(sub(w, scale(up, dot(w, up))), 1.0 / self.crossing_speed(position));
+        return add(horizontal, scale(up, local.2));
+    }
+}
+
+impl DubinsManeuver3D {
+    /// Create a new Dubins maneuver between geodetic states, planned in `plane`
+    pub fn try_new_geodetic(plane: &LocalTangentPlane, qi: &GeodeticState, qf: &GeodeticState, rhomin: f64, pitchlims: (f64, f64)) -> Result<DubinsManeuver3D, DubinsError> {
+        return DubinsManeuver3D::try_new(plane.state_from_geodetic(qi), plane.state_from_geodetic(qf), rhomin, pitchlims);
+    }
+
+    /// Geodetic states spaced `step` apart in arc length of a maneuver planned
+    /// in `plane`, see `compute_sampling_by_step`
+    pub fn compute_sampling_geodetic(&self, plane: &LocalTangentPlane, step: f64, include_endpoint: bool) -> Vec<GeodeticState> {
+        return self.compute_sampling_by_step(step, include_endpoint).iter().map(|q| plane.state_to_geodetic(q)).collect();
+    }
+}
+
+fn dot(a: (f64, f64, f64), b: (f64, f64, f64)) -> f64 {
+    return a.0 * b.0 + a.1 * b.1 + a.2 * b.2;
+}
+
+fn add(a: (f64, f64, f64), b: (f64, f64, f64)) -> (f64, f64, f64) {
+    return (a.0 + b.0, a.1 + b.1, a.2 + b.2);
+}
+
+fn sub(a: (f64, f64, f64), b: (f64, f64, f64)) -> (f64, f64, f64) {
+    return (a.0 - b.0, a.1 - b.1, a.2 - b.2);
+}
+
+fn scale(a: (f64, f64, f64), factor: f64) -> (f64, f64, f64) {
+    return (factor * a.0, factor * a.1, factor * a.2);
+}
+
+/// Linear combination of three axes
+fn combine(axes: &[(f64, f64, f64); 3], v: (f64, f64, f64)) -> (f64, f64, f64) {
+    return (
+        v.0 * axes[0].0 + v.1 * axes[1].0 + v.2 * axes[2].0,
+        v.0 * axes[0].1 + v.1 * axes[1].1 + v.2 * axes[2].1,
+        v.0 * axes[0].2 + v.1 * axes[1].2 + v.2 * axes[2].2
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mod_pi;
+
+    fn origin() -> Geodetic {
+        return Geodetic::from_degrees(47.3977, 8.5456, 488.0);
+    }
+
+    #[test]
+    fn geodetic_round_trip_through_the_plane() {
+        let plane = LocalTangentPlane::new(origin());
+        for frame in [Frame::ENU, Frame::NED] {
+            for (latitude, longitude, altitude) in [(47.3977, 8.5456, 488.0), (47.45, 8.60, 700.0), (47.0, 9.2, -50.0), (47.85, 8.0, 3000.0)] {
+                let position = Geodetic::from_degrees(latitude, longitude, altitude);
+                let back = plane.to_geodetic(plane.to_local(&position, frame), frame);
+                assert!((back.latitude - position.latitude).abs() < 1e-12);
+                assert!((back.longitude - position.longitude).abs() < 1e-12);
+                assert!((back.altitude - position.altitude).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn state_round_trip_keeps_heading_and_pitch() {
+        let plane = LocalTangentPlane::new(origin());
+        let q = GeodeticState{position: Geodetic::from_degrees(47.6, 8.9, 1200.0), heading: 5.9, pitch: -0.15};
+        let back = plane.state_to_geodetic(&plane.state_from_geodetic(&q));
+        assert!((back.position.latitude - q.position.latitude).abs() < 1e-12);
+        assert!((back.position.longitude - q.position.longitude).abs() < 1e-12);
+        assert!((back.position.altitude - q.position.altitude).abs() < 1e-6);
+        assert!(mod_pi(back.heading - q.heading).abs() < 1e-9);
+        assert!((back.pitch - q.pitch).abs() < 1e-9);
+    }
+
+    #[test]
+    fn level_flight_keeps_its_altitude() {
+        let plane = LocalTangentPlane::new(origin());
+        for distance in [10000.0, 50000.0] {
+            let position = plane.to_geodetic((0.0, distance, 0.0), Frame::ENU);
+            assert!((position.altitude - origin().altitude).abs() < 1e-9);
+            let q = plane.state_to_geodetic(&State{x: distance, y: 0.0, z: 0.0, yaw: 1.0, pitch: 0.0});
+            assert!(q.pitch.abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn samples_end_at_the_geodetic_goal() {
+        let plane = LocalTangentPlane::new(origin());
+        let qi = GeodeticState{position: Geodetic::from_degrees(47.40, 8.55, 600.0), heading: 0.0, pitch: 0.0};
+        let qf = GeodeticState{position: Geodetic::from_degrees(47.45, 8.60, 700.0), heading: 1.2, pitch: 0.0};
+        let maneuver = DubinsManeuver3D::try_new_geodetic(&plane, &qi, &qf, 80.0, (-0.2, 0.2)).unwrap();
+        let samples = maneuver.compute_sampling_geodetic(&plane, 50.0, true);
+        let last = samples.last().unwrap();
+        assert!((last.position.latitude - qf.position.latitude).abs() < 1e-10);
+        assert!((last.position.longitude - qf.position.longitude).abs() < 1e-10);
+        assert!((last.position.altitude - qf.position.altitude).abs() < 1e-4);
+        assert!(mod_pi(samples[0].heading - qi.heading).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fifty_km_leg_stays_accurate() {
+        // 50 km north of the origin and back down to the same altitude
+        let plane = LocalTangentPlane::new(origin());
+        let qi = GeodeticState{position: Geodetic::from_degrees(47.40, 8.55, 1000.0), heading: 0.3, pitch: 0.0};
+        let qf = GeodeticState{position: Geodetic::from_degrees(47.85, 8.50, 1000.0), heading: 5.9, pitch: 0.0};
+
+        for q in [qi, qf] {
+            let back = plane.state_to_geodetic(&plane.state_from_geodetic(&q));
+            assert!((back.position.latitude - q.position.latitude).abs() < 1e-12);
+            assert!((back.position.longitude - q.position.longitude).abs() < 1e-12);
+            assert!((back.position.altitude - q.position.altitude).abs() < 1e-6);
+            assert!(mod_pi(back.heading - q.heading).abs() < 1e-9);
+            assert!((back.pitch - q.pitch).abs() < 1e-9);
+        }
+
+        let maneuver = DubinsManeuver3D::try_new_geodetic(&plane, &qi, &qf, 200.0, (-0.2, 0.2)).unwrap();
+        let samples = maneuver.compute_sampling_geodetic(&plane, 100.0, true);
+        let last = samples.last().unwrap();
+        assert!((last.position.latitude - qf.position.latitude).abs() < 1e-10);
+        assert!((last.position.longitude - qf.position.longitude).abs() < 1e-10);
+
+        // Level in the frame is level above the ellipsoid, a flat plane would
+        // climb about 196 m over 50 km
+        for sample in samples.iter() {
+            assert!((sample.position.altitude - 1000.0).abs() < 1e-6);
+            assert!(sample.pitch.abs() < 1e-9);
+        }
+
+        // Flown distance matches the planned length within 0.02%
+        let flown: f64 = samples.windows(2).map(|pair| {
+            let d = sub(pair[1].position.to_ecef(), pair[0].position.to_ecef());
+            return dot(d, d).sqrt();
+        }).sum();
+        assert!((flown / maneuver.length() - 1.0).abs() < 2e-4, "{} {}", flown, maneuver.length());
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{PI, State};
+use crate::error::{DubinsError, Endpoint};
+use crate::dubins3d::DubinsManeuver3D;
+use crate::validation::{validate_pitch, validate_pitch_limits, validate_radius, validate_state};
+use crate::path::DubinsPath3D;
+
+/// Index of the best preceding state and the maneuver leading from it
+type Predecessor = Option<(usize, DubinsManeuver3D)>;
+
+/// Choose yaw and pitch at waypoints given only by position so that the
+/// total length of the connecting 3D Dubins maneuvers is minimal
+///
+/// Angles are restricted to a regular grid of `yaw_samples` yaws and
+/// `pitch_samples` pitches inside the pitch limits plus level flight. The
+/// best combination is found by dynamic programming over the waypoints,
+/// transitions whose lower bound cannot improve on the best known cost are
+/// skipped without solving the full maneuver.
+#[derive(Debug, Copy, Clone)]
+pub struct HeadingOptimizer {
+    pub rhomin: f64,
+    pub pitchlims: (f64, f64),
+    pub yaw_samples: usize,
+    pub pitch_samples: usize
+}
+
+impl HeadingOptimizer {
+    /// Optimizer with 16 yaw and 2 pitch samples in addition to level flight
+    pub fn new(rhomin: f64, pitchlims: (f64, f64)) -> Self {
+        return HeadingOptimizer{rhomin: rhomin, pitchlims: pitchlims, yaw_samples: 16, pitch_samples: 2};
+    }
+
+    /// Candidate `(yaw, pitch)` pairs tried at every free waypoint
+    pub fn candidates(&self) -> Vec<(f64, f64)> {
+        let (min, max) = self.pitchlims;
+        let mut pitches: Vec<f64> = Vec::new();
+        if min <= 0.0 && max >= 0.0 {
+            pitches.push(0.0);
+        }
+        for i in 0..self.pitch_samples {
+            let pitch = min + (max - min) * (i + 1) as f64 / (self.pitch_samples + 1) as f64;
+            if pitch != 0.0 {
+                pitches.push(pitch);
+            }
+        }
+
+        let mut candidates: Vec<(f64, f64)> = Vec::with_capacity(self.yaw_samples * pitches.len());
+        for i in 0..self.yaw_samples {
+            let yaw = 2.0 * PI * i as f64 / self.yaw_samples as f64;
+            for pitch in pitches.iter() {
+                candidates.push((yaw, *pitch));
+            }
+        }
+        return candidates;
+    }
+
+    /// Find the shortest path through `points` in the given order
+    ///
+    /// `start` and `goal` optionally fix `(yaw, pitch)` at the first and last
+    /// point, all other angles are chosen from `candidates`.
+    pub fn solve(&self, points: &[(f64, f64, f64)], start: Option<(f64, f64)>, goal: Option<(f64, f64)>) -> Result<DubinsPath3D, DubinsError> {
+        if points.len() < 2 {
+            return Err(DubinsError::TooFewWaypoints(points.len()));
+        }
+        let candidates = self.candidates();
+        if candidates.is_empty() {
+            return Err(DubinsError::SearchFailed);
+        }
+
+        let last = points.len() - 1;
+        let layers: Vec<Vec<State>> = points.iter().enumerate().map(|(i, point)| {
+            let fixed = if i == 0 { start } else if i == last { goal } else { None };
+            match fixed {
+                Some(angles) => vec![to_state(*point, angles)],
+                None => candidates.iter().map(|angles| to_state(*point, *angles)).collect()
+            }
+        }).collect();
+
+        // Validated once here, transitions use the unchecked solvers
+        validate_radius(self.rhomin)?;
+        validate_pitch_limits(self.pitchlims)?;
+        for (i, layer) in layers.iter().enumerate() {
+            let endpoint = if i == 0 { Endpoint::Start } else { Endpoint::Goal };
+            for q in layer.iter() {
+                validate_state(q, endpoint)?;
+                validate_pitch(q, endpoint, self.pitchlims)?;
+            }
+        }
+
+        let mut costs: Vec<f64> = vec![0.0; layers[0].len()];
+        let mut best: Vec<Vec<Predecessor>> = Vec::with_capacity(last);
+        for layer in 1..layers.len() {
+            let (layer_costs, layer_best) = self.relax(&layers[layer - 1], &costs, &layers[layer]);
+            costs = layer_costs;
+            best.push(layer_best);
+        }
+
+        let mut index = 0;
+        for (i, cost) in costs.iter().enumerate() {
+            if *cost < costs[index] {
+                index = i;
+            }
+        }
+        if !costs[index].is_finite() {
+            return Err(DubinsError::SearchFailed);
+        }
+
+        let mut legs: Vec<DubinsManeuver3D> = Vec::with_capacity(last);
+        for layer in (0..last).rev() {
+            let (previous, maneuver) = match best[layer][index].take() {
+                Some(entry) => entry,
+                None => return Err(DubinsError::SearchFailed)
+            };
+            legs.push(maneuver);
+            index = previous;
+        }
+        legs.reverse();
+        return DubinsPath3D::from_legs(legs);
+    }
+
+    /// Extend the shortest known paths ending in `from` by one leg to `to`,
+    /// expects validated states
+    fn relax(&self, from: &[State], costs: &[f64], to: &[State]) -> (Vec<f64>, Vec<Predecessor>) {
+        let mut order: Vec<usize> = (0..from.len()).filter(|i| costs[*i].is_finite()).collect();
+        order.sort_by(|a, b| costs[*a].total_cmp(&costs[*b]));
+
+        let mut next_costs: Vec<f64> = vec![core::f64::INFINITY; to.len()];
+        let mut next_best: Vec<Predecessor> = Vec::with_capacity(to.len());
+        for (j, qf) in to.iter().enumerate() {
+            let mut entry: Predecessor = None;
+            for i in order.iter() {
+                if costs[*i] >= next_costs[j] {
+                    break;
+                }
+                let bound = DubinsManeuver3D::solve_lower_bound(from[*i], *qf, self.rhomin, self.pitchlims);
+                if costs[*i] + bound.length() >= next_costs[j] {
+                    continue;
+                }
+                if let Ok(maneuver) = DubinsManeuver3D::solve(from[*i], *qf, self.rhomin, self.pitchlims) {
+                    if costs[*i] + maneuver.length() < next_costs[j] {
+                        next_costs[j] = costs[*i] + maneuver.length();
+                        entry = Some((*i, maneuver));
+                    }
+                }
+            }
+            next_best.push(entry);
+        }
+        return (next_costs, next_best);
+    }
+}
+
+fn to_state(point: (f64, f64, f64), angles: (f64, f64)) -> State {
+    return State{x: point.0, y: point.1, z: point.2, yaw: angles.0, pitch: angles.1};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POINTS: [(f64, f64, f64); 4] = [(0.0, 0.0, 0.0), (300.0, 100.0, 30.0), (500.0, -200.0, 10.0), (100.0, -400.0, 60.0)];
+
+    #[test]
+    fn beats_every_fixed_candidate() {
+        let optimizer = HeadingOptimizer::new(40.0, (-0.3, 0.3));
+        let path = optimizer.solve(&POINTS, None, None).unwrap();
+        for (waypoint, point) in path.waypoints().iter().zip(POINTS.iter()) {
+            assert_eq!((waypoint.x, waypoint.y, waypoint.z), *point);
+        }
+        for angles in optimizer.candidates() {
+            let waypoints: Vec<State> = POINTS.iter().map(|point| to_state(*point, angles)).collect();
+            if let Ok(fixed) = DubinsPath3D::try_new(&waypoints, 40.0, (-0.3, 0.3)) {
+                assert!(path.length() <= fixed.length() + 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_fixed_pitch_outside_limits() {
+        let optimizer = HeadingOptimizer::new(40.0, (-0.3, 0.3));
+        let result = optimizer.solve(&POINTS, Some((0.0, 0.5)), None);
+        assert_eq!(result.unwrap_err(), DubinsError::PitchOutOfLimits(Endpoint::Start, 0.5));
+    }
+}
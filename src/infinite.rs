@@ -0,0 +1,39 @@
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// Serialize a float that may be positive infinity, which is written as
+/// `None` since formats like JSON have no infinity
+///
+/// Used with `#[serde(with = "crate::infinite")]` on lengths, radii and
+/// costs that are infinite where no maneuver exists.
+pub(crate) fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    if *value == core::f64::INFINITY {
+        return serializer.serialize_none();
+    }
+    return serializer.serialize_some(value);
+}
+
+/// Read a float written by `serialize`, `None` is positive infinity
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    let value: Option<f64> = Option::deserialize(deserializer)?;
+    return Ok(value.unwrap_or(core::f64::INFINITY));
+}
+
+/// Same as the enclosing module for every float of a sequence
+pub(crate) mod seq {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde::ser::SerializeSeq;
+
+    pub(crate) fn serialize<S: Serializer>(values: &[f64], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(values.len()))?;
+        for value in values {
+            let value = if *value == core::f64::INFINITY { None } else { Some(*value) };
+            seq.serialize_element(&value)?;
+        }
+        return seq.end();
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<f64>, D::Error> {
+        let values: Vec<Option<f64>> = Vec::deserialize(deserializer)?;
+        return Ok(values.into_iter().map(|value| value.unwrap_or(core::f64::INFINITY)).collect());
+    }
+}
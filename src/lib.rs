@@ -1,3 +1,11 @@
+#![allow(
+    clippy::needless_return,
+    clippy::redundant_field_names,
+    clippy::too_many_arguments,
+    clippy::upper_case_acronyms,
+    clippy::legacy_numeric_constants
+)]
+
 pub(crate) use core::f64::consts::PI as PI;
 pub(crate) fn mod2pi(th: f64) -> f64{
     let t = th % (2.0 * PI);
@@ -7,7 +15,17 @@ pub(crate) fn mod2pi(th: f64) -> f64{
     return t;
 }
 
-#[derive(Copy, Clone)]
+/// Wrap an angle into [-pi, pi)
+pub(crate) fn mod_pi(th: f64) -> f64 {
+    return mod2pi(th + PI) - PI;
+}
+
+/// Position and direction of travel of the vehicle
+///
+/// The solver expects ENU states with z up and yaw counterclockwise from the
+/// x axis, `Frame` converts states from and into NED.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct State {
     pub x: f64,
     pub y: f64,
@@ -16,8 +34,65 @@ pub struct State {
     pub pitch: f64
 }
 
+mod error;
+#[cfg(feature = "serde")]
+mod infinite;
+mod validation;
 mod dubins2d;
+mod reeds_shepp;
 mod vertical;
 mod dubins3d;
+mod samples;
+mod path;
+mod headings;
+mod tour;
+mod matrix;
+mod vehicle;
+mod timing;
+mod bank;
+mod frame;
+mod orientation;
+mod geodesy;
+mod rrt;
+mod collision;
+mod terrain;
+mod wind;
 
-pub use crate::dubins3d::{DubinsManeuver3D};
\ No newline at end of file
+pub use crate::error::{DubinsError, Endpoint};
+pub use crate::validation::validate;
+pub use crate::dubins2d::{
+    DubinsManeuver2D,
+    DubinsStruct,
+    ManeuverCase,
+    Segment,
+    SegmentType,
+    State2D
+};
+pub use crate::reeds_shepp::{Direction, ReedsSheppManeuver2D};
+pub use crate::dubins3d::{DubinsManeuver3D, TurnRadii};
+pub use crate::samples::Samples;
+pub use crate::path::DubinsPath3D;
+pub use crate::headings::HeadingOptimizer;
+pub use crate::tour::{Tour, TourMode, TourPlanner};
+pub use crate::matrix::{CostMatrix, CostMode};
+pub use crate::vehicle::{
+    STANDARD_GRAVITY,
+    VehicleConstraints,
+    flight_path_angle,
+    turn_radius
+};
+pub use crate::timing::{SpeedProfile, TimedManeuver, TimedState};
+pub use crate::bank::{BankedState, bank_angle, bank_limited_radius};
+pub use crate::frame::Frame;
+pub use crate::orientation::{Pose, Quaternion};
+pub use crate::geodesy::{
+    WGS84_A,
+    WGS84_F,
+    Geodetic,
+    GeodeticState,
+    LocalTangentPlane
+};
+pub use crate::rrt::{CollisionChecker, RrtStarPlanner};
+pub use crate::collision::{Aabb, CurvePoint, HalfSpace, Obstacles, PolygonalPrism, Shape, Sphere, VerticalCylinder};
+pub use crate::terrain::{Heightmap, TerrainFollower};
+pub use crate::wind::{WindManeuver2D, WindManeuver3D};
@@ -0,0 +1,228 @@
+use crate::State;
+use crate::error::{DubinsError, Endpoint};
+use crate::dubins3d::DubinsManeuver3D;
+use crate::validation::{validate_pitch, validate_pitch_limits, validate_radius, validate_state};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Which length is computed for every pair of states
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CostMode {
+    /// Length of the maneuver found by `DubinsManeuver3D::new`
+    Exact,
+    /// Length from `DubinsManeuver3D::get_lower_bound`
+    LowerBound,
+    /// Length from `DubinsManeuver3D::get_upper_bound`
+    UpperBound
+}
+
+/// Dense row-major matrix of maneuver lengths, pairs without a feasible
+/// maneuver are infinite
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "CostMatrixRaw"))]
+pub struct CostMatrix {
+    rows: usize,
+    cols: usize,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::infinite::seq::serialize"))]
+    data: Vec<f64>
+}
+
+impl CostMatrix {
+    /// Compute the length of the maneuver from every state in `from` to every
+    /// state in `to`
+    ///
+    /// The inputs are validated once per state instead of once per pair. The
+    /// exact radius search of a pair solves its horizontal problem at many
+    /// radii and computes the radius independent part of it only once, the
+    /// lengths equal those of `DubinsManeuver3D::new`. With the `parallel`
+    /// feature rows are computed on the rayon thread pool.
+    pub fn compute(from: &[State], to: &[State], rhomin: f64, pitchlims: (f64, f64), mode: CostMode) -> Result<Self, DubinsError> {
+        validate_inputs(from, to, rhomin, pitchlims)?;
+        return Ok(CostMatrix::fill(from, to, |qi, qf| get_cost(qi, qf, rhomin, pitchlims, mode)));
+    }
+
+    /// Compute exact lengths of at most `max_cost`, longer maneuvers are
+    /// infinite
+    ///
+    /// Pairs whose lower bound already exceeds `max_cost` skip the radius
+    /// search, which saves most of the work when only states in range of each
+    /// other matter, as in task allocation.
+    pub fn compute_within(from: &[State], to: &[State], rhomin: f64, pitchlims: (f64, f64), max_cost: f64) -> Result<Self, DubinsError> {
+        validate_inputs(from, to, rhomin, pitchlims)?;
+        let get_cost_within = |qi: State, qf: State| {
+            if DubinsManeuver3D::solve_lower_bound(qi, qf, rhomin, pitchlims).length() > max_cost {
+                return core::f64::INFINITY;
+            }
+            let cost = get_cost(qi, qf, rhomin, pitchlims, CostMode::Exact);
+            return if cost <= max_cost { cost } else { core::f64::INFINITY };
+        };
+        return Ok(CostMatrix::fill(from, to, get_cost_within));
+    }
+
+    /// Matrix of `cost` for every pair, rows on the rayon thread pool with the
+    /// `parallel` feature
+    fn fill<F: Fn(State, State) -> f64 + Sync>(from: &[State], to: &[State], cost: F) -> Self {
+        let mut data: Vec<f64> = vec![0.0; from.len() * to.len()];
+        let fill_row = |(i, row): (usize, &mut [f64])| {
+            for (j, entry) in row.iter_mut().enumerate() {
+                *entry = cost(from[i], to[j]);
+            }
+        };
+        if !to.is_empty() {
+            #[cfg(feature = "parallel")]
+            data.par_chunks_mut(to.len()).enumerate().for_each(fill_row);
+            #[cfg(not(feature = "parallel"))]
+            data.chunks_mut(to.len()).enumerate().for_each(fill_row);
+        }
+        return CostMatrix{rows: from.len(), cols: to.len(), data: data};
+    }
+
+    /// Number of initial states
+    pub fn rows(&self) -> usize {
+        return self.rows;
+    }
+
+    /// Number of final states
+    pub fn cols(&self) -> usize {
+        return self.cols;
+    }
+
+    /// Length of the maneuver from state `i` of `from` to state `j` of `to`
+    pub fn get(&self, i: usize, j: usize) -> f64 {
+        return self.data[i * self.cols + j];
+    }
+
+    /// Lengths of all maneuvers starting at state `i` of `from`
+    pub fn row(&self, i: usize) -> &[f64] {
+        return &self.data[i * self.cols..(i + 1) * self.cols];
+    }
+
+    /// All lengths in row-major order
+    pub fn as_slice(&self) -> &[f64] {
+        return &self.data;
+    }
+}
+
+fn validate_inputs(from: &[State], to: &[State], rhomin: f64, pitchlims: (f64, f64)) -> Result<(), DubinsError> {
+    validate_radius(rhomin)?;
+    validate_pitch_limits(pitchlims)?;
+    for q in from.iter() {
+        validate_state(q, Endpoint::Start)?;
+        validate_pitch(q, Endpoint::Start, pitchlims)?;
+    }
+    for q in to.iter() {
+        validate_state(q, Endpoint::Goal)?;
+        validate_pitch(q, Endpoint::Goal, pitchlims)?;
+    }
+    return Ok(());
+}
+
+fn get_cost(qi: State, qf: State, rhomin: f64, pitchlims: (f64, f64), mode: CostMode) -> f64 {
+    return match mode {
+        CostMode::Exact => match DubinsManeuver3D::solve(qi, qf, rhomin, pitchlims) {
+            Ok(maneuver) => maneuver.length(),
+            Err(_) => core::f64::INFINITY
+        },
+        CostMode::LowerBound => DubinsManeuver3D::solve_lower_bound(qi, qf, rhomin, pitchlims).length(),
+        CostMode::UpperBound => DubinsManeuver3D::solve_upper_bound(qi, qf, rhomin, pitchlims).length()
+    };
+}
+
+/// Serialized form of `CostMatrix`, checked before it is used
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct CostMatrixRaw {
+    rows: usize,
+    cols: usize,
+    #[serde(deserialize_with = "crate::infinite::seq::deserialize")]
+    data: Vec<f64>
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<CostMatrixRaw> for CostMatrix {
+    type Error = DubinsError;
+
+    fn try_from(raw: CostMatrixRaw) -> Result<Self, DubinsError> {
+        if raw.rows.checked_mul(raw.cols) != Some(raw.data.len()) {
+            return Err(DubinsError::InconsistentData("number of costs does not match rows and columns"));
+        }
+        if raw.data.iter().any(|cost| cost.is_nan() || *cost < 0.0) {
+            return Err(DubinsError::InconsistentData("costs must not be negative or NaN"));
+        }
+        return Ok(CostMatrix{rows: raw.rows, cols: raw.cols, data: raw.data});
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn within_matches_the_full_matrix() {
+        let states: Vec<State> = (0..6).map(|i| {
+            let i = i as f64;
+            return State{x: 400.0 * i, y: 150.0 * (i * 1.7).sin(), z: 10.0 * i, yaw: i, pitch: 0.0};
+        }).collect();
+        let full = CostMatrix::compute(&states, &states, 40.0, (-0.3, 0.3), CostMode::Exact).unwrap();
+        let within = CostMatrix::compute_within(&states, &states, 40.0, (-0.3, 0.3), 700.0).unwrap();
+        for (cost, limited) in full.as_slice().iter().zip(within.as_slice()) {
+            assert_eq!(*limited, if *cost <= 700.0 { *cost } else { core::f64::INFINITY });
+        }
+        assert!(within.as_slice().iter().any(|cost| cost.is_infinite()));
+    }
+
+    #[test]
+    fn exact_costs_match_single_maneuvers() {
+        let states: Vec<State> = (0..5).map(|i| {
+            let i = i as f64;
+            return State{x: 300.0 * i.cos(), y: 250.0 * (i * 1.3).sin(), z: 15.0 * i, yaw: 1.9 * i, pitch: 0.05 * (i - 2.0)};
+        }).collect();
+        let matrix = CostMatrix::compute(&states, &states, 40.0, (-0.3, 0.3), CostMode::Exact).unwrap();
+        for (i, qi) in states.iter().enumerate() {
+            for (j, qf) in states.iter().enumerate() {
+                assert_eq!(matrix.get(i, j), DubinsManeuver3D::new(*qi, *qf, 40.0, (-0.3, 0.3)).length());
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let states = [
+            State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0},
+            State{x: 300.0, y: 100.0, z: 20.0, yaw: 1.0, pitch: 0.0}
+        ];
+        let matrix = CostMatrix::compute(&states, &states, 40.0, (-0.3, 0.3), CostMode::Exact).unwrap();
+        let json = serde_json::to_string(&matrix).unwrap();
+        let restored: CostMatrix = serde_json::from_str(&json).unwrap();
+        assert_eq!((restored.rows(), restored.cols()), (2, 2));
+        assert!((restored.get(1, 0) - matrix.get(1, 0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn infinite_costs_round_trip() {
+        let states = [
+            State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0},
+            State{x: 3000.0, y: 100.0, z: 20.0, yaw: 1.0, pitch: 0.0}
+        ];
+        let matrix = CostMatrix::compute_within(&states, &states, 40.0, (-0.3, 0.3), 1000.0).unwrap();
+        assert!(matrix.get(0, 1).is_infinite());
+        let json = serde_json::to_string(&matrix).unwrap();
+        let restored: CostMatrix = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.get(0, 1), core::f64::INFINITY);
+        assert!((restored.get(1, 1) - matrix.get(1, 1)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rejects_mismatched_dimensions() {
+        let json = r#"{"rows": 2, "cols": 2, "data": [1.0, 2.0, 3.0]}"#;
+        assert!(serde_json::from_str::<CostMatrix>(json).is_err());
+    }
+}
@@ -0,0 +1,173 @@
+use crate::State;
+use crate::frame::Frame;
+use crate::bank::BankedState;
+use crate::dubins3d::DubinsManeuver3D;
+
+/// Unit quaternion rotating body axes into world axes
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quaternion {
+    pub w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64
+}
+
+impl Quaternion {
+    /// Rotation by `yaw` about z, then `pitch` about the new y axis, then
+    /// `roll` about the new x axis
+    pub fn from_euler_zyx(yaw: f64, pitch: f64, roll: f64) -> Self {
+        let (sy, cy) = (0.5 * yaw).sin_cos();
+        let (sp, cp) = (0.5 * pitch).sin_cos();
+        let (sr, cr) = (0.5 * roll).sin_cos();
+        return Quaternion {
+            w: cr * cp * cy + sr * sp * sy,
+            x: sr * cp * cy - cr * sp * sy,
+            y: cr * sp * cy + sr * cp * sy,
+            z: cr * cp * sy - sr * sp * cy
+        };
+    }
+
+    /// Rotation matrix, rows are indexed by world axis and columns by body axis
+    pub fn to_rotation_matrix(&self) -> [[f64; 3]; 3] {
+        let Quaternion{w, x, y, z} = *self;
+        return [
+            [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y)],
+            [2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x)],
+            [2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y)]
+        ];
+    }
+
+    /// Rotate a vector from body into world axes
+    pub fn rotate(&self, v: (f64, f64, f64)) -> (f64, f64, f64) {
+        let m = self.to_rotation_matrix();
+        return (
+            m[0][0] * v.0 + m[0][1] * v.1 + m[0][2] * v.2,
+            m[1][0] * v.0 + m[1][1] * v.1 + m[1][2] * v.2,
+            m[2][0] * v.0 + m[2][1] * v.1 + m[2][2] * v.2
+        );
+    }
+}
+
+/// Position and orientation of the vehicle in a world frame
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pose {
+    pub position: (f64, f64, f64),
+    pub orientation: Quaternion,
+    pub frame: Frame
+}
+
+impl State {
+    /// Unit vector along the direction of travel in `frame`
+    pub fn direction(&self, frame: Frame) -> (f64, f64, f64) {
+        let enu = (
+            self.pitch.cos() * self.yaw.cos(),
+            self.pitch.cos() * self.yaw.sin(),
+            self.pitch.sin()
+        );
+        return frame.vector_from_enu(enu);
+    }
+
+    /// Orientation of the body in `frame`, `roll` is positive with the left
+    /// wing down as returned by `bank_angle`, zero for wings level
+    pub fn quaternion(&self, roll: f64, frame: Frame) -> Quaternion {
+        let (yaw, pitch, roll) = frame.euler_from_enu(self.yaw, self.pitch, roll);
+        return Quaternion::from_euler_zyx(yaw, pitch, roll);
+    }
+
+    /// Rotation matrix of the body in `frame`, see `quaternion`
+    pub fn rotation_matrix(&self, roll: f64, frame: Frame) -> [[f64; 3]; 3] {
+        return self.quaternion(roll, frame).to_rotation_matrix();
+    }
+
+    /// Position and orientation in `frame`, see `quaternion`
+    pub fn pose(&self, roll: f64, frame: Frame) -> Pose {
+        return Pose {
+            position: frame.vector_from_enu((self.x, self.y, self.z)),
+            orientation: self.quaternion(roll, frame),
+            frame: frame
+        };
+    }
+}
+
+impl BankedState {
+    /// Position and orientation including the bank angle in `frame`
+    pub fn pose(&self, frame: Frame) -> Pose {
+        return self.state.pose(self.roll, frame);
+    }
+}
+
+impl DubinsManeuver3D {
+    /// Wings level poses spaced `step` apart in arc length, the last item is
+    /// exactly the final pose
+    ///
+    /// Panics if `step` is not positive or the maneuver holds no path.
+    pub fn compute_sampling_poses(&self, step: f64, frame: Frame) -> Vec<Pose> {
+        return self.samples(step).map(|state| state.pose(0.0, frame)).collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PI;
+
+    fn states() -> Vec<State> {
+        let mut states = Vec::new();
+        for yaw in [0.0, 0.7, 2.0, -2.5] {
+            for pitch in [-0.4, 0.0, 0.3] {
+                states.push(State{x: 1.0, y: -2.0, z: 3.0, yaw: yaw, pitch: pitch});
+            }
+        }
+        return states;
+    }
+
+    fn close(a: (f64, f64, f64), b: (f64, f64, f64)) -> bool {
+        return (a.0 - b.0).abs() < 1e-12 && (a.1 - b.1).abs() < 1e-12 && (a.2 - b.2).abs() < 1e-12;
+    }
+
+    #[test]
+    fn quaternion_has_unit_norm() {
+        for q in states() {
+            for roll in [-1.0, 0.0, 0.5] {
+                for frame in [Frame::ENU, Frame::NED] {
+                    let Quaternion{w, x, y, z} = q.quaternion(roll, frame);
+                    assert!((w * w + x * x + y * y + z * z - 1.0).abs() < 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn body_forward_axis_is_the_direction_of_travel() {
+        for q in states() {
+            for roll in [-1.0, 0.0, 0.5] {
+                for frame in [Frame::ENU, Frame::NED] {
+                    let forward = q.quaternion(roll, frame).rotate((1.0, 0.0, 0.0));
+                    assert!(close(forward, q.direction(frame)), "{:?} {:?}", q, frame);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn euler_sign_conventions() {
+        let (yaw, pitch, roll) = (0.7, 0.3, 0.4);
+        let q = State{x: 0.0, y: 0.0, z: 0.0, yaw: yaw, pitch: pitch};
+        assert_eq!(q.quaternion(roll, Frame::ENU), Quaternion::from_euler_zyx(yaw, -pitch, -roll));
+        assert_eq!(q.quaternion(roll, Frame::NED), Quaternion::from_euler_zyx(PI / 2.0 - yaw, pitch, -roll));
+    }
+
+    #[test]
+    fn left_bank_lowers_the_left_wing() {
+        // Heading north with the left wing down
+        let q = State{x: 0.0, y: 0.0, z: 0.0, yaw: PI / 2.0, pitch: 0.0};
+        let enu = q.quaternion(0.4, Frame::ENU);
+        assert!(enu.rotate((0.0, 1.0, 0.0)).2 < 0.0);
+        assert!(enu.rotate((0.0, 0.0, 1.0)).0 < 0.0);
+        let ned = q.quaternion(0.4, Frame::NED);
+        assert!(ned.rotate((0.0, 1.0, 0.0)).2 < 0.0);
+        assert!(ned.rotate((0.0, 0.0, 1.0)).1 > 0.0);
+    }
+}
@@ -0,0 +1,246 @@
+use crate::{State, mod_pi};
+use crate::error::DubinsError;
+use crate::dubins3d::DubinsManeuver3D;
+#[cfg(feature = "serde")]
+use crate::dubins3d::nearly_equal;
+
+/// Largest difference in position or angle accepted between the goal of a
+/// leg and the start of the next one
+const CONTINUITY_TOLERANCE: f64 = 1e-6;
+
+/// Chain of 3D Dubins maneuvers through an ordered sequence of waypoints
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "DubinsPath3DRaw"))]
+pub struct DubinsPath3D {
+    legs: Vec<DubinsManeuver3D>,
+    offsets: Vec<f64>,
+    length: f64
+}
+
+impl DubinsPath3D {
+    /// Connect consecutive waypoints with 3D Dubins maneuvers
+    ///
+    /// Panics if any leg cannot be constructed, see `try_new` for a fallible variant.
+    pub fn new(waypoints: &[State], rhomin: f64, pitchlims: (f64, f64)) -> Self {
+        match DubinsPath3D::try_new(waypoints, rhomin, pitchlims) {
+            Ok(path) => path,
+            Err(err) => panic!("unable to construct 3D Dubins path: {}", err)
+        }
+    }
+
+    /// Connect consecutive waypoints with 3D Dubins maneuvers, returning the
+    /// first error encountered
+    pub fn try_new(waypoints: &[State], rhomin: f64, pitchlims: (f64, f64)) -> Result<Self, DubinsError> {
+        if waypoints.len() < 2 {
+            return Err(DubinsError::TooFewWaypoints(waypoints.len()));
+        }
+        let mut legs: Vec<DubinsManeuver3D> = Vec::with_capacity(waypoints.len() - 1);
+        for pair in waypoints.windows(2) {
+            legs.push(DubinsManeuver3D::try_new(pair[0], pair[1], rhomin, pitchlims)?);
+        }
+        return DubinsPath3D::from_legs(legs);
+    }
+
+    /// Build a path from already solved maneuvers, each leg has to start
+    /// where the previous one ends
+    ///
+    /// Bounds from `get_lower_bound` or `get_upper_bound` are rejected, they
+    /// hold no path or one that turns tighter than allowed.
+    pub fn from_legs(legs: Vec<DubinsManeuver3D>) -> Result<Self, DubinsError> {
+        if legs.is_empty() {
+            return Err(DubinsError::TooFewWaypoints(0));
+        }
+        for (index, leg) in legs.iter().enumerate() {
+            if !has_path(leg) {
+                return Err(DubinsError::LegWithoutPath(index));
+            }
+            if index > 0 && !continues(&legs[index - 1].goal(), &leg.start()) {
+                return Err(DubinsError::DiscontinuousLegs(index));
+            }
+        }
+        let mut offsets: Vec<f64> = Vec::with_capacity(legs.len());
+        let mut length = 0.0;
+        for leg in legs.iter() {
+            offsets.push(length);
+            length += leg.length();
+        }
+        return Ok(DubinsPath3D{legs: legs, offsets: offsets, length: length});
+    }
+
+    /// Total length of all legs
+    pub fn length(&self) -> f64 {
+        return self.length;
+    }
+
+    /// Maneuvers connecting consecutive waypoints
+    pub fn legs(&self) -> &[DubinsManeuver3D] {
+        return &self.legs;
+    }
+
+    /// Maneuver of the leg with the given index
+    pub fn leg(&self, index: usize) -> Option<&DubinsManeuver3D> {
+        return self.legs.get(index);
+    }
+
+    /// Number of legs, one less than the number of waypoints
+    pub fn num_legs(&self) -> usize {
+        return self.legs.len();
+    }
+
+    /// Waypoints the path passes through, including the first and last state
+    pub fn waypoints(&self) -> Vec<State> {
+        let mut waypoints: Vec<State> = self.legs.iter().map(|leg| leg.start()).collect();
+        waypoints.push(self.legs[self.legs.len() - 1].goal());
+        return waypoints;
+    }
+
+    /// Arc length along the whole path at which the given leg starts
+    pub fn leg_offset(&self, index: usize) -> Option<f64> {
+        return self.offsets.get(index).copied();
+    }
+
+    /// Index of the leg containing arc length `s`, a leg boundary belongs to
+    /// the leg starting there
+    pub fn leg_index_at(&self, s: f64) -> usize {
+        let index = self.offsets.partition_point(|offset| *offset <= s);
+        return index.max(1) - 1;
+    }
+
+    /// State at arc length `s` along the whole path, clamped to the path
+    pub fn state_at(&self, s: f64) -> State {
+        let index = self.leg_index_at(s);
+        return self.legs[index].state_at(s - self.offsets[index]);
+    }
+
+    /// States spaced `step` apart in arc length along the whole path,
+    /// optionally followed by the exact final state
+    ///
+    /// Panics if `step` is not positive.
+    pub fn compute_sampling_by_step(&self, step: f64, include_endpoint: bool) -> Vec<State> {
+        assert!(step > 0.0, "sampling step must be positive, got {}", step);
+        let number_of_samples = (self.length / step).ceil() as usize;
+        let mut points: Vec<State> = Vec::with_capacity(number_of_samples + 1);
+        for sample in 0..number_of_samples {
+            points.push(self.state_at(step * sample as f64));
+        }
+        if include_endpoint {
+            points.push(self.legs[self.legs.len() - 1].goal());
+        }
+        return points;
+    }
+}
+
+/// Serialized form of `DubinsPath3D`, rebuilt with `from_legs` and compared
+/// against the stored offsets
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct DubinsPath3DRaw {
+    legs: Vec<DubinsManeuver3D>,
+    offsets: Vec<f64>,
+    length: f64
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<DubinsPath3DRaw> for DubinsPath3D {
+    type Error = DubinsError;
+
+    fn try_from(raw: DubinsPath3DRaw) -> Result<Self, DubinsError> {
+        let path = DubinsPath3D::from_legs(raw.legs)?;
+        let offsets_match = path.offsets.len() == raw.offsets.len()
+            && path.offsets.iter().zip(raw.offsets.iter()).all(|(a, b)| nearly_equal(*a, *b));
+        if !offsets_match || !nearly_equal(path.length, raw.length) {
+            return Err(DubinsError::InconsistentData("offsets or length do not match the legs"));
+        }
+        return Ok(path);
+    }
+}
+
+/// Whether a maneuver holds a flyable path rather than only a length bound
+fn has_path(leg: &DubinsManeuver3D) -> bool {
+    let flyable = matches!(leg.horizontal_radius(), Some(radius) if radius >= leg.rhomin());
+    return flyable && leg.longitudinal().is_some() && leg.length().is_finite();
+}
+
+/// Whether `next` starts where `previous` ends, angles compared modulo 2pi
+fn continues(previous: &State, next: &State) -> bool {
+    let offsets = [
+        next.x - previous.x,
+        next.y - previous.y,
+        next.z - previous.z,
+        mod_pi(next.yaw - previous.yaw),
+        mod_pi(next.pitch - previous.pitch)
+    ];
+    return offsets.iter().all(|offset| offset.abs() <= CONTINUITY_TOLERANCE);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PITCHLIMS: (f64, f64) = (-0.3, 0.3);
+
+    fn waypoints() -> [State; 3] {
+        return [
+            State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0},
+            State{x: 200.0, y: 50.0, z: 20.0, yaw: 1.0, pitch: 0.0},
+            State{x: 100.0, y: 300.0, z: 0.0, yaw: 3.0, pitch: 0.0}
+        ];
+    }
+
+    #[test]
+    fn accepts_chained_legs() {
+        let path = DubinsPath3D::new(&waypoints(), 40.0, PITCHLIMS);
+        let rebuilt = DubinsPath3D::from_legs(path.legs().to_vec()).unwrap();
+        assert_eq!(rebuilt.length(), path.length());
+    }
+
+    #[test]
+    fn rejects_discontinuous_legs() {
+        let [a, b, c] = waypoints();
+        let legs = vec![DubinsManeuver3D::new(a, b, 40.0, PITCHLIMS), DubinsManeuver3D::new(a, c, 40.0, PITCHLIMS)];
+        assert_eq!(DubinsPath3D::from_legs(legs).unwrap_err(), DubinsError::DiscontinuousLegs(1));
+    }
+
+    #[test]
+    fn rejects_bounds() {
+        let [a, b, _] = waypoints();
+        let bound = DubinsManeuver3D::get_lower_bound(a, b, 40.0, PITCHLIMS).unwrap();
+        assert_eq!(DubinsPath3D::from_legs(vec![bound]).unwrap_err(), DubinsError::LegWithoutPath(0));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    fn path() -> DubinsPath3D {
+        let waypoints = [
+            State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0},
+            State{x: 200.0, y: 50.0, z: 20.0, yaw: 1.0, pitch: 0.0},
+            State{x: 100.0, y: 300.0, z: 0.0, yaw: 3.0, pitch: 0.0}
+        ];
+        return DubinsPath3D::new(&waypoints, 40.0, (-0.3, 0.3));
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = path();
+        let json = serde_json::to_string(&path).unwrap();
+        let restored: DubinsPath3D = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.length(), path.length());
+        let (a, b) = (restored.state_at(300.0), path.state_at(300.0));
+        assert!((a.x - b.x).abs() + (a.y - b.y).abs() + (a.z - b.z).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rejects_inconsistent_paths() {
+        let mut value = serde_json::to_value(path()).unwrap();
+        value["legs"] = serde_json::json!([]);
+        assert!(serde_json::from_value::<DubinsPath3D>(value).is_err());
+
+        let mut value = serde_json::to_value(path()).unwrap();
+        value["offsets"] = serde_json::json!([0.0]);
+        assert!(serde_json::from_value::<DubinsPath3D>(value).is_err());
+    }
+}
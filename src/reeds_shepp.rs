@@ -0,0 +1,690 @@
+use core::fmt;
+
+use crate::{PI, mod2pi, mod_pi};
+use crate::error::{DubinsError, Endpoint};
+use crate::validation::{validate_radius, validate_state2d};
+#[cfg(feature = "serde")]
+use crate::dubins3d::nearly_equal;
+use crate::dubins2d::{
+    Segment,
+    SegmentType,
+    State2D,
+    get_position_in_directed_segment,
+    get_segment
+};
+use crate::dubins2d::SegmentType::{LEFT as L, RIGHT as R, STRAIGHT as S, NONE as N};
+
+/// Tolerance on normalized segment lengths when checking the sign conditions
+const ZERO: f64 = 10.0 * core::f64::EPSILON;
+
+/// Segment types of the 18 base words, time flipping and reflecting them
+/// yields the 48 words of the Reeds-Shepp family
+const WORDS: [[SegmentType; 5]; 18] = [
+    [L, R, L, N, N],
+    [R, L, R, N, N],
+    [L, R, L, R, N],
+    [R, L, R, L, N],
+    [L, R, S, L, N],
+    [R, L, S, R, N],
+    [L, S, R, L, N],
+    [R, S, L, R, N],
+    [L, R, S, R, N],
+    [R, L, S, L, N],
+    [R, S, R, L, N],
+    [L, S, L, R, N],
+    [L, S, R, N, N],
+    [R, S, L, N, N],
+    [L, S, L, N, N],
+    [R, S, R, N, N],
+    [L, R, S, L, R],
+    [R, L, S, R, L]
+];
+
+/// Solver for one family of words, updating the best candidate
+type Family = fn(f64, f64, f64, &mut Candidate);
+
+const FAMILIES: [Family; 5] = [_csc, _ccc, _cccc, _ccsc, _ccscc];
+
+/// Driving direction of a segment
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    Forward,
+    Backward
+}
+
+impl Direction {
+    fn of(length: f64) -> Self {
+        if length < 0.0 { Direction::Backward } else { Direction::Forward }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Direction::Forward => write!(f, "+"),
+            Direction::Backward => write!(f, "-"),
+        }
+    }
+}
+
+/// Shortest planar path for a vehicle that can drive forward and in reverse
+///
+/// A deserialized maneuver is checked for a valid radius and endpoints and
+/// for segments that add up to its length.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "ReedsSheppManeuver2DRaw"))]
+pub struct ReedsSheppManeuver2D {
+    qi: (f64, f64, f64),
+    qf: (f64, f64, f64),
+    rhomin: f64,
+    /// Segment types with signed lengths normalized by `rhomin`, negative
+    /// lengths are driven in reverse
+    path: Vec<(SegmentType, f64)>,
+    length: f64
+}
+
+impl ReedsSheppManeuver2D {
+    /// Create the shortest Reeds-Shepp maneuver between two configurations
+    ///
+    /// Panics if no maneuver can be constructed, see `try_new` for a fallible variant.
+    pub fn new(qi: State2D, qf: State2D, rhomin: f64) -> Self {
+        match ReedsSheppManeuver2D::try_new(qi, qf, rhomin) {
+            Ok(maneuver) => maneuver,
+            Err(err) => panic!("unable to construct Reeds-Shepp maneuver: {}", err)
+        }
+    }
+
+    /// Create the shortest Reeds-Shepp maneuver, returning an error for invalid inputs
+    pub fn try_new(qi: State2D, qf: State2D, rhomin: f64) -> Result<Self, DubinsError> {
+        validate_radius(rhomin)?;
+        validate_state2d(&qi, Endpoint::Start)?;
+        validate_state2d(&qf, Endpoint::Goal)?;
+        return ReedsSheppManeuver2D::solve(qi, qf, rhomin, &FAMILIES);
+    }
+
+    /// Shortest maneuver among the words of `families`, expects validated inputs
+    fn solve(qi: State2D, qf: State2D, rhomin: f64, families: &[Family]) -> Result<Self, DubinsError> {
+        // Normalize the problem using rotation and scaling
+        let dx = qf.x - qi.x;
+        let dy = qf.y - qi.y;
+        let (s, c) = (qi.yaw.sin(), qi.yaw.cos());
+        let x = (c * dx + s * dy) / rhomin;
+        let y = (-s * dx + c * dy) / rhomin;
+        let phi = mod_pi(qf.yaw - qi.yaw);
+
+        let mut best = Candidate{word: WORDS[0], lengths: [0.0; 5], length: core::f64::INFINITY};
+        for family in families {
+            family(x, y, phi, &mut best);
+        }
+        if !best.length.is_finite() {
+            return Err(DubinsError::SearchFailed);
+        }
+
+        let path = best.word.iter().zip(best.lengths.iter())
+            .filter(|(kind, _)| **kind != SegmentType::NONE)
+            .map(|(kind, length)| (*kind, *length))
+            .collect();
+        return Ok(ReedsSheppManeuver2D {
+            qi: (qi.x, qi.y, qi.yaw),
+            qf: (qf.x, qf.y, qf.yaw),
+            rhomin: rhomin,
+            path: path,
+            length: best.length * rhomin
+        });
+    }
+
+    /// Initial configuration of the maneuver
+    pub fn start(&self) -> State2D {
+        return State2D{x: self.qi.0, y: self.qi.1, yaw: self.qi.2};
+    }
+
+    /// Final configuration of the maneuver
+    pub fn goal(&self) -> State2D {
+        return State2D{x: self.qf.0, y: self.qf.1, yaw: self.qf.2};
+    }
+
+    /// Minimum turning radius used for the arcs
+    pub fn rhomin(&self) -> f64 {
+        return self.rhomin;
+    }
+
+    /// Total driven distance, reverse segments count positively
+    pub fn length(&self) -> f64 {
+        return self.length;
+    }
+
+    /// Word of the maneuver with driving directions, e.g. L+S-R+
+    pub fn word(&self) -> String {
+        return self.path.iter()
+            .map(|(kind, length)| format!("{:?}{}", kind, Direction::of(*length)))
+            .collect();
+    }
+
+    /// Configuration after driving the distance `offset`, clamped to the maneuver
+    pub fn state_at(&self, offset: f64) -> State2D {
+        let q = get_coordinates_at(self, offset.max(0.0).min(self.length));
+        return State2D{x: q.0, y: q.1, yaw: q.2};
+    }
+
+    /// Evenly spaced configurations along the maneuver, excluding the final one
+    pub fn compute_sampling(&self, number_of_samples: i32) -> Vec<State2D> {
+        let mut points: Vec<State2D> = Vec::new();
+        for sample in 0..number_of_samples {
+            let prog = self.length * (sample as f64) / (number_of_samples as f64);
+            points.push(self.state_at(prog));
+        }
+        return points;
+    }
+
+    /// Describe the segments of the maneuver in order with their driving direction
+    pub fn segments(&self) -> Vec<(Segment, Direction)> {
+        let mut segments: Vec<(Segment, Direction)> = Vec::with_capacity(self.path.len());
+        let mut offset = 0.0;
+        let mut start = self.start();
+        for (i, (kind, length)) in self.path.iter().enumerate() {
+            let distance = length.abs() * self.rhomin;
+            offset += distance;
+            let end = if i + 1 == self.path.len() { self.state_at(self.length) } else { self.state_at(offset) };
+            segments.push((get_segment(*kind, start, end, distance, self.rhomin), Direction::of(*length)));
+            start = end;
+        }
+        return segments;
+    }
+}
+
+/// Serialized form of `ReedsSheppManeuver2D`, checked before it is used
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct ReedsSheppManeuver2DRaw {
+    qi: (f64, f64, f64),
+    qf: (f64, f64, f64),
+    rhomin: f64,
+    path: Vec<(SegmentType, f64)>,
+    length: f64
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<ReedsSheppManeuver2DRaw> for ReedsSheppManeuver2D {
+    type Error = DubinsError;
+
+    fn try_from(raw: ReedsSheppManeuver2DRaw) -> Result<Self, DubinsError> {
+        validate_radius(raw.rhomin)?;
+        validate_state2d(&State2D{x: raw.qi.0, y: raw.qi.1, yaw: raw.qi.2}, Endpoint::Start)?;
+        validate_state2d(&State2D{x: raw.qf.0, y: raw.qf.1, yaw: raw.qf.2}, Endpoint::Goal)?;
+
+        if raw.path.is_empty() || raw.path.len() > 5 {
+            return Err(DubinsError::InconsistentData("path needs one to five segments"));
+        }
+        if raw.path.iter().any(|(kind, length)| *kind == SegmentType::NONE || !length.is_finite()) {
+            return Err(DubinsError::InconsistentData("segments need a type and a finite length"));
+        }
+        let length: f64 = raw.path.iter().map(|(_, length)| length.abs()).sum::<f64>() * raw.rhomin;
+        if !nearly_equal(raw.length, length) {
+            return Err(DubinsError::InconsistentData("length differs from the segments"));
+        }
+        return Ok(ReedsSheppManeuver2D{qi: raw.qi, qf: raw.qf, rhomin: raw.rhomin, path: raw.path, length: raw.length});
+    }
+}
+
+fn get_coordinates_at(maneuver: &ReedsSheppManeuver2D, offset: f64) -> (f64, f64, f64) {
+    let mut remaining = offset / maneuver.rhomin;
+    let mut q: (f64, f64, f64) = (0.0, 0.0, maneuver.qi.2);
+    for (kind, length) in maneuver.path.iter() {
+        let distance = remaining.min(length.abs());
+        q = get_position_in_directed_segment(distance, q, *kind, *length);
+        remaining -= distance;
+        if remaining <= 0.0 {
+            break;
+        }
+    }
+    q.0 = q.0 * maneuver.rhomin + maneuver.qi.0;
+    q.1 = q.1 * maneuver.rhomin + maneuver.qi.1;
+    q.2 = mod2pi(q.2);
+    return q;
+}
+
+/// Best word found so far with signed normalized segment lengths
+struct Candidate {
+    word: [SegmentType; 5],
+    lengths: [f64; 5],
+    length: f64
+}
+
+impl Candidate {
+    fn consider(&mut self, word: usize, lengths: [f64; 5]) {
+        let length: f64 = lengths.iter().map(|l| l.abs()).sum();
+        if length < self.length {
+            self.word = WORDS[word];
+            self.lengths = lengths;
+            self.length = length;
+        }
+    }
+}
+
+fn polar(x: f64, y: f64) -> (f64, f64) {
+    return ((x * x + y * y).sqrt(), y.atan2(x));
+}
+
+fn tau_omega(u: f64, v: f64, xi: f64, eta: f64, phi: f64) -> (f64, f64) {
+    let delta = mod_pi(u - v);
+    let a = u.sin() - delta.sin();
+    let b = u.cos() - delta.cos() - 1.0;
+    let t1 = (eta * a - xi * b).atan2(xi * a + eta * b);
+    let t2 = 2.0 * (delta.cos() - v.cos() - u.cos()) + 3.0;
+    let tau = if t2 < 0.0 { mod_pi(t1 + PI) } else { mod_pi(t1) };
+    let omega = mod_pi(tau - u + v - phi);
+    return (tau, omega);
+}
+
+fn _lp_sp_lp(x: f64, y: f64, phi: f64) -> Option<(f64, f64, f64)> {
+    let (u, t) = polar(x - phi.sin(), y - 1.0 + phi.cos());
+    if t >= -ZERO {
+        let v = mod_pi(phi - t);
+        if v >= -ZERO {
+            return Some((t, u, v));
+        }
+    }
+    return None;
+}
+
+fn _lp_sp_rp(x: f64, y: f64, phi: f64) -> Option<(f64, f64, f64)> {
+    let (u1, t1) = polar(x + phi.sin(), y - 1.0 - phi.cos());
+    let u1 = u1 * u1;
+    if u1 >= 4.0 {
+        let u = (u1 - 4.0).sqrt();
+        let theta = 2.0_f64.atan2(u);
+        let t = mod_pi(t1 + theta);
+        let v = mod_pi(t - phi);
+        if t >= -ZERO && v >= -ZERO {
+            return Some((t, u, v));
+        }
+    }
+    return None;
+}
+
+fn _csc(x: f64, y: f64, phi: f64, best: &mut Candidate) {
+    if let Some((t, u, v)) = _lp_sp_lp(x, y, phi) {
+        best.consider(14, [t, u, v, 0.0, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_sp_lp(-x, y, -phi) {
+        best.consider(14, [-t, -u, -v, 0.0, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_sp_lp(x, -y, -phi) {
+        best.consider(15, [t, u, v, 0.0, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_sp_lp(-x, -y, phi) {
+        best.consider(15, [-t, -u, -v, 0.0, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_sp_rp(x, y, phi) {
+        best.consider(12, [t, u, v, 0.0, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_sp_rp(-x, y, -phi) {
+        best.consider(12, [-t, -u, -v, 0.0, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_sp_rp(x, -y, -phi) {
+        best.consider(13, [t, u, v, 0.0, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_sp_rp(-x, -y, phi) {
+        best.consider(13, [-t, -u, -v, 0.0, 0.0]);
+    }
+}
+
+fn _lp_rm_l(x: f64, y: f64, phi: f64) -> Option<(f64, f64, f64)> {
+    let xi = x - phi.sin();
+    let eta = y - 1.0 + phi.cos();
+    let (u1, theta) = polar(xi, eta);
+    if u1 <= 4.0 {
+        let u = -2.0 * (0.25 * u1).asin();
+        let t = mod_pi(theta + 0.5 * u + PI);
+        let v = mod_pi(phi - t + u);
+        if t >= -ZERO && u <= ZERO {
+            return Some((t, u, v));
+        }
+    }
+    return None;
+}
+
+fn _ccc(x: f64, y: f64, phi: f64, best: &mut Candidate) {
+    if let Some((t, u, v)) = _lp_rm_l(x, y, phi) {
+        best.consider(0, [t, u, v, 0.0, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rm_l(-x, y, -phi) {
+        best.consider(0, [-t, -u, -v, 0.0, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rm_l(x, -y, -phi) {
+        best.consider(1, [t, u, v, 0.0, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rm_l(-x, -y, phi) {
+        best.consider(1, [-t, -u, -v, 0.0, 0.0]);
+    }
+
+    // Backwards words
+    let xb = x * phi.cos() + y * phi.sin();
+    let yb = x * phi.sin() - y * phi.cos();
+    if let Some((t, u, v)) = _lp_rm_l(xb, yb, phi) {
+        best.consider(0, [v, u, t, 0.0, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rm_l(-xb, yb, -phi) {
+        best.consider(0, [-v, -u, -t, 0.0, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rm_l(xb, -yb, -phi) {
+        best.consider(1, [v, u, t, 0.0, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rm_l(-xb, -yb, phi) {
+        best.consider(1, [-v, -u, -t, 0.0, 0.0]);
+    }
+}
+
+fn _lp_rup_lum_rm(x: f64, y: f64, phi: f64) -> Option<(f64, f64, f64)> {
+    let xi = x + phi.sin();
+    let eta = y - 1.0 - phi.cos();
+    let rho = 0.25 * (2.0 + (xi * xi + eta * eta).sqrt());
+    if rho <= 1.0 {
+        let u = rho.acos();
+        let (t, v) = tau_omega(u, -u, xi, eta, phi);
+        if t >= -ZERO && v <= ZERO {
+            return Some((t, u, v));
+        }
+    }
+    return None;
+}
+
+fn _lp_rum_lum_rp(x: f64, y: f64, phi: f64) -> Option<(f64, f64, f64)> {
+    let xi = x + phi.sin();
+    let eta = y - 1.0 - phi.cos();
+    let rho = (20.0 - xi * xi - eta * eta) / 16.0;
+    if (0.0..=1.0).contains(&rho) {
+        let u = -rho.acos();
+        if u >= -0.5 * PI {
+            let (t, v) = tau_omega(u, u, xi, eta, phi);
+            if t >= -ZERO && v >= -ZERO {
+                return Some((t, u, v));
+            }
+        }
+    }
+    return None;
+}
+
+fn _cccc(x: f64, y: f64, phi: f64, best: &mut Candidate) {
+    if let Some((t, u, v)) = _lp_rup_lum_rm(x, y, phi) {
+        best.consider(2, [t, u, -u, v, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rup_lum_rm(-x, y, -phi) {
+        best.consider(2, [-t, -u, u, -v, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rup_lum_rm(x, -y, -phi) {
+        best.consider(3, [t, u, -u, v, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rup_lum_rm(-x, -y, phi) {
+        best.consider(3, [-t, -u, u, -v, 0.0]);
+    }
+
+    if let Some((t, u, v)) = _lp_rum_lum_rp(x, y, phi) {
+        best.consider(2, [t, u, u, v, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rum_lum_rp(-x, y, -phi) {
+        best.consider(2, [-t, -u, -u, -v, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rum_lum_rp(x, -y, -phi) {
+        best.consider(3, [t, u, u, v, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rum_lum_rp(-x, -y, phi) {
+        best.consider(3, [-t, -u, -u, -v, 0.0]);
+    }
+}
+
+fn _lp_rm_sm_lm(x: f64, y: f64, phi: f64) -> Option<(f64, f64, f64)> {
+    let xi = x - phi.sin();
+    let eta = y - 1.0 + phi.cos();
+    let (rho, theta) = polar(xi, eta);
+    if rho >= 2.0 {
+        let r = (rho * rho - 4.0).sqrt();
+        let u = 2.0 - r;
+        let t = mod_pi(theta + r.atan2(-2.0));
+        let v = mod_pi(phi - 0.5 * PI - t);
+        if t >= -ZERO && u <= ZERO && v <= ZERO {
+            return Some((t, u, v));
+        }
+    }
+    return None;
+}
+
+fn _lp_rm_sm_rm(x: f64, y: f64, phi: f64) -> Option<(f64, f64, f64)> {
+    let xi = x + phi.sin();
+    let eta = y - 1.0 - phi.cos();
+    let (rho, theta) = polar(-eta, xi);
+    if rho >= 2.0 {
+        let t = theta;
+        let u = 2.0 - rho;
+        let v = mod_pi(t + 0.5 * PI - phi);
+        if t >= -ZERO && u <= ZERO && v <= ZERO {
+            return Some((t, u, v));
+        }
+    }
+    return None;
+}
+
+fn _ccsc(x: f64, y: f64, phi: f64, best: &mut Candidate) {
+    let half = 0.5 * PI;
+    if let Some((t, u, v)) = _lp_rm_sm_lm(x, y, phi) {
+        best.consider(4, [t, -half, u, v, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rm_sm_lm(-x, y, -phi) {
+        best.consider(4, [-t, half, -u, -v, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rm_sm_lm(x, -y, -phi) {
+        best.consider(5, [t, -half, u, v, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rm_sm_lm(-x, -y, phi) {
+        best.consider(5, [-t, half, -u, -v, 0.0]);
+    }
+
+    if let Some((t, u, v)) = _lp_rm_sm_rm(x, y, phi) {
+        best.consider(8, [t, -half, u, v, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rm_sm_rm(-x, y, -phi) {
+        best.consider(8, [-t, half, -u, -v, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rm_sm_rm(x, -y, -phi) {
+        best.consider(9, [t, -half, u, v, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rm_sm_rm(-x, -y, phi) {
+        best.consider(9, [-t, half, -u, -v, 0.0]);
+    }
+
+    // Backwards words
+    let xb = x * phi.cos() + y * phi.sin();
+    let yb = x * phi.sin() - y * phi.cos();
+    if let Some((t, u, v)) = _lp_rm_sm_lm(xb, yb, phi) {
+        best.consider(6, [v, u, -half, t, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rm_sm_lm(-xb, yb, -phi) {
+        best.consider(6, [-v, -u, half, -t, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rm_sm_lm(xb, -yb, -phi) {
+        best.consider(7, [v, u, -half, t, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rm_sm_lm(-xb, -yb, phi) {
+        best.consider(7, [-v, -u, half, -t, 0.0]);
+    }
+
+    if let Some((t, u, v)) = _lp_rm_sm_rm(xb, yb, phi) {
+        best.consider(10, [v, u, -half, t, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rm_sm_rm(-xb, yb, -phi) {
+        best.consider(10, [-v, -u, half, -t, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rm_sm_rm(xb, -yb, -phi) {
+        best.consider(11, [v, u, -half, t, 0.0]);
+    }
+    if let Some((t, u, v)) = _lp_rm_sm_rm(-xb, -yb, phi) {
+        best.consider(11, [-v, -u, half, -t, 0.0]);
+    }
+}
+
+fn _lp_rm_s_lm_rp(x: f64, y: f64, phi: f64) -> Option<(f64, f64, f64)> {
+    let xi = x + phi.sin();
+    let eta = y - 1.0 - phi.cos();
+    let (rho, _) = polar(xi, eta);
+    if rho >= 2.0 {
+        let u = 4.0 - (rho * rho - 4.0).sqrt();
+        if u <= ZERO {
+            let t = mod_pi(((4.0 - u) * xi - 2.0 * eta).atan2(-2.0 * xi + (u - 4.0) * eta));
+            let v = mod_pi(t - phi);
+            if t >= -ZERO && v >= -ZERO {
+                return Some((t, u, v));
+            }
+        }
+    }
+    return None;
+}
+
+fn _ccscc(x: f64, y: f64, phi: f64, best: &mut Candidate) {
+    let half = 0.5 * PI;
+    if let Some((t, u, v)) = _lp_rm_s_lm_rp(x, y, phi) {
+        best.consider(16, [t, -half, u, -half, v]);
+    }
+    if let Some((t, u, v)) = _lp_rm_s_lm_rp(-x, y, -phi) {
+        best.consider(16, [-t, half, -u, half, -v]);
+    }
+    if let Some((t, u, v)) = _lp_rm_s_lm_rp(x, -y, -phi) {
+        best.consider(17, [t, -half, u, -half, v]);
+    }
+    if let Some((t, u, v)) = _lp_rm_s_lm_rp(-x, -y, phi) {
+        best.consider(17, [-t, half, -u, half, -v]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dubins2d::DubinsManeuver2D;
+
+    fn goals() -> Vec<State2D> {
+        let mut goals = Vec::new();
+        for i in 0..9 {
+            for j in 0..9 {
+                for k in 0..8 {
+                    let x = -8.0 + 2.0 * i as f64;
+                    let y = -8.0 + 2.0 * j as f64;
+                    goals.push(State2D{x: x, y: y, yaw: -PI + PI * k as f64 / 4.0 + 0.1});
+                }
+            }
+        }
+        return goals;
+    }
+
+    fn reaches(maneuver: &ReedsSheppManeuver2D, goal: State2D) -> bool {
+        let end = maneuver.state_at(maneuver.length());
+        let last = maneuver.segments().last().unwrap().0.end;
+        return (end.x - goal.x).hypot(end.y - goal.y) < 1e-6
+            && mod_pi(end.yaw - goal.yaw).abs() < 1e-6
+            && (last.x - goal.x).hypot(last.y - goal.y) < 1e-6;
+    }
+
+    #[test]
+    fn every_family_ends_at_the_goal() {
+        let rhomin = 1.5;
+        for family in FAMILIES {
+            let mut solved = 0;
+            let mut reversing = 0;
+            for qi in [State2D{x: 0.0, y: 0.0, yaw: 0.0}, State2D{x: 1.0, y: -2.0, yaw: 2.5}] {
+                for qf in goals() {
+                    for (from, to) in [(qi, qf), (qf, qi)] {
+                        let maneuver = match ReedsSheppManeuver2D::solve(from, to, rhomin, &[family]) {
+                            Ok(maneuver) => maneuver,
+                            Err(_) => continue
+                        };
+                        assert!(reaches(&maneuver, to), "{} misses {:?}", maneuver.word(), to);
+                        solved += 1;
+                        if maneuver.segments().iter().any(|(_, direction)| *direction == Direction::Backward) {
+                            reversing += 1;
+                        }
+                    }
+                }
+            }
+            assert!(solved > 0 && reversing > 0);
+        }
+    }
+
+    #[test]
+    fn reversed_problem_has_the_same_length() {
+        let qi = State2D{x: 0.0, y: 0.0, yaw: 0.0};
+        for qf in goals() {
+            let forward = ReedsSheppManeuver2D::new(qi, qf, 1.0);
+            let backward = ReedsSheppManeuver2D::new(qf, qi, 1.0);
+            assert!(reaches(&forward, qf) && reaches(&backward, qi));
+            assert!((forward.length() - backward.length()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn lengths_are_bounded_by_dubins_and_heading_change() {
+        let qi = State2D{x: 0.0, y: 0.0, yaw: 0.0};
+        for qf in goals() {
+            let length = ReedsSheppManeuver2D::new(qi, qf, 1.0).length();
+            assert!(length <= DubinsManeuver2D::new(qi, qf, 1.0).length() + 1e-9);
+            assert!(length >= qf.x.hypot(qf.y) - 1e-9);
+            assert!(length >= mod_pi(qf.yaw).abs() - 1e-9);
+        }
+    }
+
+    /// Straight lines and single arcs are optimal, the latter because the
+    /// heading turns by at most the distance over `rhomin`
+    #[test]
+    fn closed_form_lengths() {
+        let r = 2.0;
+        let qi = State2D{x: 0.0, y: 0.0, yaw: 0.0};
+        let cases = [
+            (State2D{x: 5.0, y: 0.0, yaw: 0.0}, 5.0),
+            (State2D{x: -5.0, y: 0.0, yaw: 0.0}, 5.0),
+            (State2D{x: r, y: r, yaw: 0.5 * PI}, 0.5 * PI * r),
+            (State2D{x: -r, y: r, yaw: -0.5 * PI}, 0.5 * PI * r),
+            (State2D{x: -r, y: -r, yaw: 0.5 * PI}, 0.5 * PI * r),
+            (State2D{x: 0.0, y: 2.0 * r, yaw: PI}, PI * r)
+        ];
+        for (qf, length) in cases {
+            let maneuver = ReedsSheppManeuver2D::new(qi, qf, r);
+            assert!((maneuver.length() - length).abs() < 1e-9, "{} {}", maneuver.word(), maneuver.length());
+            assert!(reaches(&maneuver, qf));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    fn maneuver() -> ReedsSheppManeuver2D {
+        let qi = State2D{x: 0.0, y: 0.0, yaw: 0.0};
+        let qf = State2D{x: -30.0, y: 20.0, yaw: 2.5};
+        return ReedsSheppManeuver2D::new(qi, qf, 10.0);
+    }
+
+    #[test]
+    fn round_trip() {
+        let maneuver = maneuver();
+        let json = serde_json::to_string(&maneuver).unwrap();
+        let restored: ReedsSheppManeuver2D = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.word(), maneuver.word());
+        assert!(nearly_equal(restored.length(), maneuver.length()));
+    }
+
+    #[test]
+    fn rejects_invalid_maneuvers() {
+        let corruptions = [
+            ("rhomin", serde_json::json!(-10.0)),
+            ("length", serde_json::json!(1.0)),
+            ("path", serde_json::json!([])),
+            ("path", serde_json::json!([["NONE", 1.0]])),
+            ("qf", serde_json::json!([1.0, 2.0, null]))
+        ];
+        for (field, value) in corruptions {
+            let mut json = serde_json::to_value(maneuver()).unwrap();
+            json[field] = value.clone();
+            assert!(serde_json::from_value::<ReedsSheppManeuver2D>(json).is_err(), "{} {}", field, value);
+        }
+    }
+}
@@ -0,0 +1,413 @@
+use std::time::{Duration, Instant};
+
+use crate::{PI, State, mod2pi, mod_pi};
+use crate::error::DubinsError;
+use crate::validation::validate;
+use crate::dubins3d::DubinsManeuver3D;
+use crate::path::DubinsPath3D;
+
+/// Decides whether states and maneuvers are free of collisions
+///
+/// Any `Fn(&State) -> bool` returning `true` for free states is a checker.
+pub trait CollisionChecker {
+    /// Whether the vehicle at `state` is free of collisions
+    fn is_state_free(&self, state: &State) -> bool;
+
+    /// Whether the whole maneuver is free of collisions, by default checks
+    /// states `resolution` apart along the arc length
+    ///
+    /// Checkers that test the maneuver exactly may use `resolution` as their
+    /// tolerance instead.
+    fn is_maneuver_free(&self, maneuver: &DubinsManeuver3D, resolution: f64) -> bool {
+        return maneuver.samples(resolution).all(|q| self.is_state_free(&q));
+    }
+}
+
+impl<F: Fn(&State) -> bool> CollisionChecker for F {
+    fn is_state_free(&self, state: &State) -> bool {
+        return self(state);
+    }
+}
+
+/// Asymptotically optimal sampling-based planner connecting two states
+/// around obstacles with 3D Dubins maneuvers
+///
+/// States are sampled within the box spanned by `lower` and `upper`. Once a
+/// path is known and `informed` is set, positions are drawn from the
+/// ellipsoid of states that can still improve it, and samples whose lower
+/// bounds through them exceed the best length are pruned. The same `seed`
+/// gives the same plan unless the time budget runs out.
+#[derive(Debug, Copy, Clone)]
+pub struct RrtStarPlanner {
+    pub rhomin: f64,
+    pub pitchlims: (f64, f64),
+    pub lower: (f64, f64, f64),
+    pub upper: (f64, f64, f64),
+    pub max_iterations: usize,
+    pub time_budget: Duration,
+    /// Probability of sampling the goal itself
+    pub goal_bias: f64,
+    /// Number of nearby nodes considered as parents and for rewiring
+    pub neighbors: usize,
+    /// Longest maneuver added to the tree in one step
+    pub max_extension: f64,
+    /// Arc length between the states checked along a maneuver by checkers
+    /// that sample, obstacles thinner than this may be missed
+    pub resolution: f64,
+    pub informed: bool,
+    pub seed: u64
+}
+
+impl RrtStarPlanner {
+    /// Informed planner with 2000 iterations and a budget of ten seconds,
+    /// checking maneuvers every tenth of `rhomin`
+    pub fn new(rhomin: f64, pitchlims: (f64, f64), lower: (f64, f64, f64), upper: (f64, f64, f64)) -> Self {
+        return RrtStarPlanner {
+            rhomin: rhomin,
+            pitchlims: pitchlims,
+            lower: lower,
+            upper: upper,
+            max_iterations: 2000,
+            time_budget: Duration::from_secs(10),
+            goal_bias: 0.05,
+            neighbors: 15,
+            max_extension: 10.0 * rhomin,
+            resolution: 0.1 * rhomin,
+            informed: true,
+            seed: 0
+        };
+    }
+
+    /// Plan a collision-free path from `start` to `goal`
+    pub fn solve<C: CollisionChecker + ?Sized>(&self, start: State, goal: State, checker: &C) -> Result<DubinsPath3D, DubinsError> {
+        validate(&start, &goal, self.rhomin, self.pitchlims)?;
+        if !(self.resolution > 0.0 && self.resolution.is_finite()) {
+            return Err(DubinsError::NonPositiveResolution(self.resolution));
+        }
+        if !checker.is_state_free(&start) || !checker.is_state_free(&goal) {
+            return Err(DubinsError::NoPathFound);
+        }
+        let deadline = Instant::now() + self.time_budget;
+        let mut tree = Tree{planner: self, checker: checker, goal: goal, nodes: Vec::new(), goal_edges: Vec::new()};
+        tree.nodes.push(Node{state: start, parent: None, cost: 0.0, edge: None, children: Vec::new()});
+        tree.connect_goal(0);
+
+        let mut rng = Rng::new(self.seed);
+        for _ in 0..self.max_iterations {
+            if Instant::now() >= deadline {
+                break;
+            }
+            let best = tree.best_goal().map(|(_, cost)| cost).unwrap_or(core::f64::INFINITY);
+            let sample = self.sample(&mut rng, start, goal, best);
+            if best.is_finite() && self.informed {
+                let through = self.lower_bound(start, sample) + self.lower_bound(sample, goal);
+                if through >= best {
+                    continue;
+                }
+            }
+            tree.extend(sample);
+        }
+
+        let (index, _) = tree.best_goal().ok_or(DubinsError::NoPathFound)?;
+        let mut legs: Vec<DubinsManeuver3D> = vec![tree.goal_edges[index].1.clone()];
+        let mut node = tree.goal_edges[index].0;
+        while let Some(parent) = tree.nodes[node].parent {
+            legs.push(tree.nodes[node].edge.clone().expect("non-root node without an edge"));
+            node = parent;
+        }
+        legs.reverse();
+        return DubinsPath3D::from_legs(legs);
+    }
+
+    fn lower_bound(&self, qi: State, qf: State) -> f64 {
+        return DubinsManeuver3D::solve_lower_bound(qi, qf, self.rhomin, self.pitchlims).length();
+    }
+
+    fn sample(&self, rng: &mut Rng, start: State, goal: State, best: f64) -> State {
+        if rng.uniform() < self.goal_bias {
+            return goal;
+        }
+        let mut position = None;
+        if self.informed && best.is_finite() {
+            // Dubins maneuvers are never shorter than the straight line, so
+            // improving states lie within the ellipsoid around start and goal
+            for _ in 0..100 {
+                let p = sample_ellipsoid(rng, start, goal, best);
+                if self.contains(p) {
+                    position = Some(p);
+                    break;
+                }
+            }
+        }
+        let (x, y, z) = position.unwrap_or_else(|| (
+            rng.range(self.lower.0, self.upper.0),
+            rng.range(self.lower.1, self.upper.1),
+            rng.range(self.lower.2, self.upper.2)
+        ));
+        return State {
+            x: x,
+            y: y,
+            z: z,
+            yaw: rng.range(0.0, 2.0 * PI),
+            pitch: rng.range(self.pitchlims.0, self.pitchlims.1)
+        };
+    }
+
+    fn contains(&self, p: (f64, f64, f64)) -> bool {
+        let within = |v: f64, a: f64, b: f64| v >= a.min(b) && v <= a.max(b);
+        return within(p.0, self.lower.0, self.upper.0)
+            && within(p.1, self.lower.1, self.upper.1)
+            && within(p.2, self.lower.2, self.upper.2);
+    }
+}
+
+struct Node {
+    state: State,
+    parent: Option<usize>,
+    /// Length of the path from the start
+    cost: f64,
+    /// Maneuver from the parent
+    edge: Option<DubinsManeuver3D>,
+    children: Vec<usize>
+}
+
+struct Tree<'a, C: CollisionChecker + ?Sized> {
+    planner: &'a RrtStarPlanner,
+    checker: &'a C,
+    goal: State,
+    nodes: Vec<Node>,
+    /// Collision-free maneuvers from tree nodes to the goal
+    goal_edges: Vec<(usize, DubinsManeuver3D)>
+}
+
+impl<'a, C: CollisionChecker + ?Sized> Tree<'a, C> {
+    /// Index into `goal_edges` and length of the shortest path to the goal
+    fn best_goal(&self) -> Option<(usize, f64)> {
+        let mut best: Option<(usize, f64)> = None;
+        let mut best_cost = core::f64::INFINITY;
+        for (index, (node, edge)) in self.goal_edges.iter().enumerate() {
+            let cost = self.nodes[*node].cost + edge.length();
+            if cost < best_cost {
+                best = Some((index, cost));
+                best_cost = cost;
+            }
+        }
+        return best;
+    }
+
+    /// Collision-free maneuver between two states
+    fn connect(&self, qi: State, qf: State) -> Option<DubinsManeuver3D> {
+        let maneuver = DubinsManeuver3D::solve(qi, qf, self.planner.rhomin, self.planner.pitchlims).ok()?;
+        if !self.checker.is_maneuver_free(&maneuver, self.planner.resolution) {
+            return None;
+        }
+        return Some(maneuver);
+    }
+
+    fn extend(&mut self, sample: State) {
+        let nearest = self.nearest(&sample, 1)[0];
+        let Ok(mut edge) = DubinsManeuver3D::solve(self.nodes[nearest].state, sample, self.planner.rhomin, self.planner.pitchlims) else {
+            return;
+        };
+        let mut target = sample;
+        if edge.length() > self.planner.max_extension {
+            target = edge.state_at(self.planner.max_extension);
+            target.yaw = mod2pi(target.yaw);
+            target.pitch = mod_pi(target.pitch).max(self.planner.pitchlims.0).min(self.planner.pitchlims.1);
+            match DubinsManeuver3D::solve(self.nodes[nearest].state, target, self.planner.rhomin, self.planner.pitchlims) {
+                Ok(maneuver) => edge = maneuver,
+                Err(_) => return
+            }
+        }
+        if !self.checker.is_maneuver_free(&edge, self.planner.resolution) {
+            return;
+        }
+
+        // Choose the parent giving the shortest path among the neighbours
+        let near = self.nearest(&target, self.planner.neighbors.max(1));
+        let mut parent = nearest;
+        let mut cost = self.nodes[nearest].cost + edge.length();
+        for &i in near.iter() {
+            if i == nearest || self.nodes[i].cost + self.planner.lower_bound(self.nodes[i].state, target) >= cost {
+                continue;
+            }
+            if let Some(maneuver) = self.connect(self.nodes[i].state, target) {
+                if self.nodes[i].cost + maneuver.length() < cost {
+                    parent = i;
+                    cost = self.nodes[i].cost + maneuver.length();
+                    edge = maneuver;
+                }
+            }
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(Node{state: target, parent: Some(parent), cost: cost, edge: Some(edge), children: Vec::new()});
+        self.nodes[parent].children.push(index);
+
+        // Rewire neighbours through the new node where that is shorter, a
+        // positive edge length keeps ancestors of the new node out of this
+        for &i in near.iter() {
+            if i == parent || cost + self.planner.lower_bound(target, self.nodes[i].state) >= self.nodes[i].cost {
+                continue;
+            }
+            if let Some(maneuver) = self.connect(target, self.nodes[i].state) {
+                if cost + maneuver.length() < self.nodes[i].cost {
+                    self.rewire(i, index, maneuver);
+                }
+            }
+        }
+
+        self.connect_goal(index);
+    }
+
+    fn connect_goal(&mut self, index: usize) {
+        let best = self.best_goal().map(|(_, cost)| cost).unwrap_or(core::f64::INFINITY);
+        let state = self.nodes[index].state;
+        if self.nodes[index].cost + self.planner.lower_bound(state, self.goal) >= best {
+            return;
+        }
+        if let Some(maneuver) = self.connect(state, self.goal) {
+            self.goal_edges.push((index, maneuver));
+        }
+    }
+
+    fn rewire(&mut self, child: usize, parent: usize, edge: DubinsManeuver3D) {
+        if let Some(old) = self.nodes[child].parent {
+            self.nodes[old].children.retain(|c| *c != child);
+        }
+        let delta = self.nodes[parent].cost + edge.length() - self.nodes[child].cost;
+        self.nodes[child].parent = Some(parent);
+        self.nodes[child].edge = Some(edge);
+        self.nodes[parent].children.push(child);
+
+        let mut stack = vec![child];
+        while let Some(node) = stack.pop() {
+            self.nodes[node].cost += delta;
+            stack.extend(self.nodes[node].children.iter().copied());
+        }
+    }
+
+    /// Indices of the `k` nodes closest to `q` in straight line distance
+    fn nearest(&self, q: &State, k: usize) -> Vec<usize> {
+        let distance = |n: &Node| (n.state.x - q.x).powi(2) + (n.state.y - q.y).powi(2) + (n.state.z - q.z).powi(2);
+        let mut indices: Vec<usize> = (0..self.nodes.len()).collect();
+        let k = k.min(indices.len());
+        if k < indices.len() {
+            indices.select_nth_unstable_by(k - 1, |a, b| distance(&self.nodes[*a]).total_cmp(&distance(&self.nodes[*b])));
+            indices.truncate(k);
+        }
+        indices.sort_by(|a, b| distance(&self.nodes[*a]).total_cmp(&distance(&self.nodes[*b])));
+        return indices;
+    }
+}
+
+/// Uniform position within the prolate spheroid whose foci are the start and
+/// goal positions and whose points have a summed focal distance of `best`
+fn sample_ellipsoid(rng: &mut Rng, start: State, goal: State, best: f64) -> (f64, f64, f64) {
+    let axis = (goal.x - start.x, goal.y - start.y, goal.z - start.z);
+    let focal = (axis.0 * axis.0 + axis.1 * axis.1 + axis.2 * axis.2).sqrt();
+    let e1 = if focal > 0.0 { (axis.0 / focal, axis.1 / focal, axis.2 / focal) } else { (1.0, 0.0, 0.0) };
+    // Any vector not parallel to e1 completes an orthonormal basis
+    let helper = if e1.0.abs() < 0.9 { (1.0, 0.0, 0.0) } else { (0.0, 1.0, 0.0) };
+    let e2 = normalize(cross(e1, helper));
+    let e3 = cross(e1, e2);
+
+    let major = 0.5 * best;
+    let minor = 0.5 * (best * best - focal * focal).max(0.0).sqrt();
+    let u = loop {
+        let u = (rng.range(-1.0, 1.0), rng.range(-1.0, 1.0), rng.range(-1.0, 1.0));
+        if u.0 * u.0 + u.1 * u.1 + u.2 * u.2 <= 1.0 {
+            break u;
+        }
+    };
+    let center = (0.5 * (start.x + goal.x), 0.5 * (start.y + goal.y), 0.5 * (start.z + goal.z));
+    return (
+        center.0 + major * u.0 * e1.0 + minor * (u.1 * e2.0 + u.2 * e3.0),
+        center.1 + major * u.0 * e1.1 + minor * (u.1 * e2.1 + u.2 * e3.1),
+        center.2 + major * u.0 * e1.2 + minor * (u.1 * e2.2 + u.2 * e3.2)
+    );
+}
+
+fn cross(a: (f64, f64, f64), b: (f64, f64, f64)) -> (f64, f64, f64) {
+    return (a.1 * b.2 - a.2 * b.1, a.2 * b.0 - a.0 * b.2, a.0 * b.1 - a.1 * b.0);
+}
+
+fn normalize(v: (f64, f64, f64)) -> (f64, f64, f64) {
+    let norm = (v.0 * v.0 + v.1 * v.1 + v.2 * v.2).sqrt();
+    return (v.0 / norm, v.1 / norm, v.2 / norm);
+}
+
+/// Xorshift generator seeded through splitmix64, keeps plans reproducible
+/// without depending on an external crate
+struct Rng {
+    state: u64
+}
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        let mut z = seed.wrapping_add(0x9E3779B97F4A7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^= z >> 31;
+        return Rng{state: if z == 0 { 1 } else { z }};
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        return self.state;
+    }
+
+    /// Uniform in [0, 1)
+    fn uniform(&mut self) -> f64 {
+        return (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+    }
+
+    fn range(&mut self, a: f64, b: f64) -> f64 {
+        return a + (b - a) * self.uniform();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collision::{Obstacles, VerticalCylinder};
+
+    const START: State = State{x: 0.0, y: 0.0, z: 50.0, yaw: 0.0, pitch: 0.0};
+    const GOAL: State = State{x: 400.0, y: 0.0, z: 50.0, yaw: 0.0, pitch: 0.0};
+
+    fn planner() -> RrtStarPlanner {
+        let mut planner = RrtStarPlanner::new(30.0, (-0.3, 0.3), (-100.0, -300.0, 0.0), (500.0, 300.0, 150.0));
+        planner.max_iterations = 300;
+        return planner;
+    }
+
+    #[test]
+    fn plans_around_a_sampled_obstacle() {
+        let free = |q: &State| (q.x - 200.0).hypot(q.y) > 60.0;
+        let path = planner().solve(START, GOAL, &free).unwrap();
+
+        assert!(path.length() > 400.0);
+        assert!(path.compute_sampling_by_step(0.05, true).iter().all(free));
+    }
+
+    #[test]
+    fn plans_around_an_exact_obstacle() {
+        let mut obstacles = Obstacles::new();
+        obstacles.push(VerticalCylinder::new((200.0, 0.0), 60.0, 0.0, 200.0));
+        let path = planner().solve(START, GOAL, &obstacles).unwrap();
+
+        assert!(path.first_contact(&obstacles).is_none());
+        let waypoints = path.waypoints();
+        assert_eq!((waypoints[0], *waypoints.last().unwrap()), (START, GOAL));
+    }
+
+    #[test]
+    fn rejects_non_positive_resolution() {
+        let mut planner = planner();
+        planner.resolution = 0.0;
+        let free = |_: &State| true;
+        assert_eq!(planner.solve(START, GOAL, &free).err(), Some(DubinsError::NonPositiveResolution(0.0)));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::State;
+use crate::dubins2d::{
+    DubinsManeuver2D,
+    SegmentStarts,
+    get_coordinates_with
+};
+
+/// Lazy iterator over states of a 3D maneuver spaced `step` apart in arc
+/// length, finishing exactly at the final state
+///
+/// Created by `DubinsManeuver3D::samples`.
+pub struct Samples<'a> {
+    dlat: &'a DubinsManeuver2D,
+    dlon: &'a DubinsManeuver2D,
+    lat_starts: SegmentStarts,
+    lon_starts: SegmentStarts,
+    qf: State,
+    step: f64,
+    count: usize,
+    front: usize,
+    back: usize
+}
+
+impl<'a> Samples<'a> {
+    pub(crate) fn new(dlat: &'a DubinsManeuver2D, dlon: &'a DubinsManeuver2D, qf: State, length: f64, step: f64) -> Self {
+        assert!(step > 0.0, "sampling step must be positive, got {}", step);
+        let count = (length / step).ceil() as usize + 1;
+        return Samples {
+            dlat: dlat,
+            dlon: dlon,
+            lat_starts: SegmentStarts::new(dlat),
+            lon_starts: SegmentStarts::new(dlon),
+            qf: qf,
+            step: step,
+            count: count,
+            front: 0,
+            back: count
+        };
+    }
+
+    fn state(&self, index: usize) -> State {
+        // The last index is reserved for the exact final state
+        if index + 1 == self.count {
+            return self.qf;
+        }
+        let q_sz = get_coordinates_with(self.dlon, &self.lon_starts, self.step * index as f64);
+        let q_xy = get_coordinates_with(self.dlat, &self.lat_starts, q_sz.0);
+        return State{x: q_xy.0, y: q_xy.1, z: q_sz.1, yaw: q_xy.2, pitch: q_sz.2};
+    }
+
+}
+
+impl Iterator for Samples<'_> {
+    type Item = State;
+
+    fn next(&mut self) -> Option<State> {
+        if self.front >= self.back {
+            return None;
+        }
+        let state = self.state(self.front);
+        self.front += 1;
+        return Some(state);
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        return (remaining, Some(remaining));
+    }
+}
+
+impl DoubleEndedIterator for Samples<'_> {
+    fn next_back(&mut self) -> Option<State> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        return Some(self.state(self.back));
+    }
+}
+
+impl ExactSizeIterator for Samples<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::State;
+    use crate::dubins3d::DubinsManeuver3D;
+
+    fn maneuver() -> DubinsManeuver3D {
+        let qi = State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+        let qf = State{x: 120.0, y: 90.0, z: 60.0, yaw: 2.5, pitch: -0.1};
+        return DubinsManeuver3D::new(qi, qf, 40.0, (-0.4, 0.4));
+    }
+
+    #[test]
+    fn len_decrements_from_both_ends() {
+        let maneuver = maneuver();
+        let mut samples = maneuver.samples(7.0);
+        let count = (maneuver.length() / 7.0).ceil() as usize + 1;
+        assert_eq!(samples.len(), count);
+
+        let mut remaining = count;
+        let mut from_front = true;
+        while remaining > 0 {
+            let item = if from_front { samples.next() } else { samples.next_back() };
+            assert!(item.is_some());
+            remaining -= 1;
+            assert_eq!(samples.len(), remaining);
+            assert_eq!(samples.size_hint(), (remaining, Some(remaining)));
+            from_front = !from_front;
+        }
+        assert_eq!(samples.next(), None);
+        assert_eq!(samples.next_back(), None);
+        assert_eq!(samples.len(), 0);
+    }
+
+    #[test]
+    fn reversed_order_matches_forward_order() {
+        let maneuver = maneuver();
+        let forward: Vec<State> = maneuver.samples(3.0).collect();
+        let mut backward: Vec<State> = maneuver.samples(3.0).rev().collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+        assert_eq!(forward.first(), Some(&maneuver.start()));
+        assert_eq!(forward.last(), Some(&maneuver.goal()));
+    }
+
+    #[test]
+    fn ends_meet_in_the_middle() {
+        let maneuver = maneuver();
+        let forward: Vec<State> = maneuver.samples(5.0).collect();
+        let mut samples = maneuver.samples(5.0);
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        while let Some(q) = samples.next() {
+            front.push(q);
+            match samples.next_back() {
+                Some(q) => back.push(q),
+                None => break
+            }
+        }
+        back.reverse();
+        front.extend(back);
+        assert_eq!(front, forward);
+    }
+}
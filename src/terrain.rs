@@ -0,0 +1,528 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::State;
+use crate::error::DubinsError;
+use crate::dubins3d::DubinsManeuver3D;
+use crate::path::DubinsPath3D;
+use crate::validation::{validate_clearance, validate_pitch_limits, validate_radius};
+
+/// Terrain height on a regular grid, bilinearly interpolated between grid
+/// points
+///
+/// Grid points lie at `origin + (col, row) * cell_size` in the x-y plane of
+/// the solver, row 0 is the southern-most row.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "HeightmapRaw"))]
+pub struct Heightmap {
+    origin: (f64, f64),
+    cell_size: f64,
+    cols: usize,
+    rows: usize,
+    heights: Vec<f64>
+}
+
+/// Serialized form of `Heightmap`, checked by `Heightmap::new`
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct HeightmapRaw {
+    origin: (f64, f64),
+    cell_size: f64,
+    cols: usize,
+    rows: usize,
+    heights: Vec<f64>
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<HeightmapRaw> for Heightmap {
+    type Error = DubinsError;
+
+    fn try_from(raw: HeightmapRaw) -> Result<Self, DubinsError> {
+        return Heightmap::new(raw.origin, raw.cell_size, raw.cols, raw.rows, raw.heights);
+    }
+}
+
+impl Heightmap {
+    /// Heightmap from heights in row-major order starting with the southern
+    /// row, returning an error if the grid is inconsistent
+    pub fn new(origin: (f64, f64), cell_size: f64, cols: usize, rows: usize, heights: Vec<f64>) -> Result<Self, DubinsError> {
+        if !origin.0.is_finite() || !origin.1.is_finite() {
+            return Err(DubinsError::InvalidHeightmap("origin is not finite"));
+        }
+        if !cell_size.is_finite() || cell_size <= 0.0 {
+            return Err(DubinsError::InvalidHeightmap("cell size must be positive"));
+        }
+        if cols < 2 || rows < 2 {
+            return Err(DubinsError::InvalidHeightmap("grid needs at least two rows and columns"));
+        }
+        if cols.checked_mul(rows) != Some(heights.len()) {
+            return Err(DubinsError::InvalidHeightmap("number of heights does not match the grid size"));
+        }
+        if heights.iter().any(|h| !h.is_finite()) {
+            return Err(DubinsError::InvalidHeightmap("heights must be finite"));
+        }
+        return Ok(Heightmap{origin: origin, cell_size: cell_size, cols: cols, rows: rows, heights: heights});
+    }
+
+    /// Parse a plain ASCII grid, rows of whitespace separated heights from
+    /// north to south
+    ///
+    /// An optional ESRI header (`ncols`, `nrows`, `xllcorner` or `xllcenter`,
+    /// `yllcorner` or `yllcenter`, `cellsize`, `nodata_value`) places the
+    /// grid, without one the cell size is 1 and the south-west grid point
+    /// lies at the origin. Grids containing no-data values are rejected.
+    pub fn from_ascii_grid(text: &str) -> Result<Self, DubinsError> {
+        let mut cols: Option<usize> = None;
+        let mut rows: Option<usize> = None;
+        let mut lower_left = (0.0, 0.0);
+        let mut corner = (false, false);
+        let mut cell_size = 1.0;
+        let mut nodata: Option<f64> = None;
+
+        let mut values: Vec<f64> = Vec::new();
+        let mut widths: Vec<usize> = Vec::new();
+        for line in text.lines() {
+            let mut tokens = line.split_whitespace();
+            let first = match tokens.next() {
+                Some(token) => token,
+                None => continue
+            };
+
+            if first.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                if !values.is_empty() {
+                    return Err(DubinsError::InvalidHeightmap("header line after grid values"));
+                }
+                let value: f64 = tokens.next()
+                    .and_then(|token| token.parse().ok())
+                    .ok_or(DubinsError::InvalidHeightmap("malformed header line"))?;
+                match first.to_ascii_lowercase().as_str() {
+                    "ncols" => cols = Some(parse_count(value)?),
+                    "nrows" => rows = Some(parse_count(value)?),
+                    "xllcorner" => { lower_left.0 = value; corner.0 = true; },
+                    "xllcenter" => { lower_left.0 = value; corner.0 = false; },
+                    "yllcorner" => { lower_left.1 = value; corner.1 = true; },
+                    "yllcenter" => { lower_left.1 = value; corner.1 = false; },
+                    "cellsize" => cell_size = value,
+                    "nodata_value" => nodata = Some(value),
+                    _ => return Err(DubinsError::InvalidHeightmap("unknown header key"))
+                }
+                continue;
+            }
+
+            let start = values.len();
+            for token in core::iter::once(first).chain(tokens) {
+                let value: f64 = token.parse().map_err(|_| DubinsError::InvalidHeightmap("grid value is not a number"))?;
+                values.push(value);
+            }
+            widths.push(values.len() - start);
+        }
+
+        let cols = match cols {
+            Some(cols) => cols,
+            None => {
+                // Without a header every line is one row
+                let cols = widths.first().copied().unwrap_or(0);
+                if widths.iter().any(|width| *width != cols) {
+                    return Err(DubinsError::InvalidHeightmap("rows have different lengths"));
+                }
+                cols
+            }
+        };
+        let rows = match rows {
+            Some(rows) => rows,
+            None => values.len().checked_div(cols).unwrap_or(0)
+        };
+        if values.len() != cols * rows {
+            return Err(DubinsError::InvalidHeightmap("number of heights does not match the grid size"));
+        }
+        if let Some(nodata) = nodata {
+            if values.contains(&nodata) {
+                return Err(DubinsError::InvalidHeightmap("grid contains no-data values"));
+            }
+        }
+
+        // Corner registration places the grid points at the cell centers
+        let origin = (
+            if corner.0 { lower_left.0 + 0.5 * cell_size } else { lower_left.0 },
+            if corner.1 { lower_left.1 + 0.5 * cell_size } else { lower_left.1 }
+        );
+        return Heightmap::new(origin, cell_size, cols, rows, north_first_to_south_first(&values, cols));
+    }
+
+    /// Heightmap from little-endian 32-bit floats in row-major order from
+    /// north to south, as in ESRI `.flt` files
+    ///
+    /// Grids containing the `nodata` value of the accompanying header, often
+    /// -9999, are rejected.
+    pub fn from_raw_f32(bytes: &[u8], cols: usize, rows: usize, origin: (f64, f64), cell_size: f64, nodata: Option<f32>) -> Result<Self, DubinsError> {
+        let size = cols.checked_mul(rows).and_then(|count| count.checked_mul(4))
+            .ok_or(DubinsError::InvalidHeightmap("grid size overflows"))?;
+        if bytes.len() != size {
+            return Err(DubinsError::InvalidHeightmap("number of bytes does not match the grid size"));
+        }
+        let values: Vec<f32> = bytes.chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect();
+        if let Some(nodata) = nodata {
+            if values.contains(&nodata) {
+                return Err(DubinsError::InvalidHeightmap("grid contains no-data values"));
+            }
+        }
+        let values: Vec<f64> = values.into_iter().map(f64::from).collect();
+        return Heightmap::new(origin, cell_size, cols, rows, north_first_to_south_first(&values, cols));
+    }
+
+    /// Read a plain ASCII grid file, see `from_ascii_grid`
+    ///
+    /// Malformed grids are reported as `InvalidData`.
+    pub fn read_ascii_grid<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        return Heightmap::from_ascii_grid(&text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err));
+    }
+
+    /// Read a raw float file, see `from_raw_f32`
+    ///
+    /// Malformed grids are reported as `InvalidData`.
+    pub fn read_raw_f32<P: AsRef<Path>>(path: P, cols: usize, rows: usize, origin: (f64, f64), cell_size: f64, nodata: Option<f32>) -> io::Result<Self> {
+        let bytes = fs::read(path)?;
+        return Heightmap::from_raw_f32(&bytes, cols, rows, origin, cell_size, nodata)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err));
+    }
+
+    /// Position of the south-west grid point
+    pub fn origin(&self) -> (f64, f64) {
+        return self.origin;
+    }
+
+    /// Spacing of the grid points
+    pub fn cell_size(&self) -> f64 {
+        return self.cell_size;
+    }
+
+    /// Number of grid points from west to east
+    pub fn cols(&self) -> usize {
+        return self.cols;
+    }
+
+    /// Number of grid points from south to north
+    pub fn rows(&self) -> usize {
+        return self.rows;
+    }
+
+    /// Height at grid point `col`, `row`
+    ///
+    /// Panics if the grid point does not exist.
+    pub fn height(&self, col: usize, row: usize) -> f64 {
+        assert!(col < self.cols && row < self.rows, "grid point ({}, {}) outside of the heightmap", col, row);
+        return self.heights[row * self.cols + col];
+    }
+
+    /// Interpolated terrain height at `x`, `y`, `None` outside of the grid
+    pub fn height_at(&self, x: f64, y: f64) -> Option<f64> {
+        let u = (x - self.origin.0) / self.cell_size;
+        let v = (y - self.origin.1) / self.cell_size;
+        if !(u >= 0.0 && v >= 0.0 && u <= (self.cols - 1) as f64 && v <= (self.rows - 1) as f64) {
+            return None;
+        }
+        let col = (u.floor() as usize).min(self.cols - 2);
+        let row = (v.floor() as usize).min(self.rows - 2);
+        let fu = u - col as f64;
+        let fv = v - row as f64;
+        let south = (1.0 - fu) * self.height(col, row) + fu * self.height(col + 1, row);
+        let north = (1.0 - fu) * self.height(col, row + 1) + fu * self.height(col + 1, row + 1);
+        return Some((1.0 - fv) * south + fv * north);
+    }
+
+    /// Height of `state` above the terrain, `None` outside of the grid
+    pub fn clearance(&self, state: &State) -> Option<f64> {
+        return self.height_at(state.x, state.y).map(|h| state.z - h);
+    }
+
+    /// Range of grid indices covering the square of half width `radius`
+    /// around `x`, `y`, limited to the grid
+    fn index_range_around(&self, x: f64, y: f64, radius: f64) -> ((usize, usize), (usize, usize)) {
+        let index_range = |center: f64, origin: f64, count: usize| {
+            let low = ((center - radius - origin) / self.cell_size).floor().max(0.0) as usize;
+            let high = ((center + radius - origin) / self.cell_size).ceil().max(0.0) as usize;
+            return (low.min(count - 1), high.min(count - 1));
+        };
+        return (index_range(x, self.origin.0, self.cols), index_range(y, self.origin.1, self.rows));
+    }
+
+    /// Highest terrain within the square of half width `radius` around `x`,
+    /// `y`, limited to the grid
+    fn max_height_around(&self, x: f64, y: f64, radius: f64) -> f64 {
+        let ((col_low, col_high), (row_low, row_high)) = self.index_range_around(x, y, radius);
+        let mut highest = self.height_at(x, y).unwrap_or(core::f64::NEG_INFINITY);
+        for row in row_low..=row_high {
+            for col in col_low..=col_high {
+                highest = highest.max(self.height(col, row));
+            }
+        }
+        return highest;
+    }
+
+    /// Steepest slope of the terrain within the square of half width
+    /// `radius` around `x`, `y`, limited to the grid
+    ///
+    /// Within a cell the bilinear slope along x lies between the slopes of
+    /// the southern and northern edge, and likewise along y.
+    fn max_slope_around(&self, x: f64, y: f64, radius: f64) -> f64 {
+        let ((col_low, col_high), (row_low, row_high)) = self.index_range_around(x, y, radius);
+        let mut rise: (f64, f64) = (0.0, 0.0);
+        for row in row_low..=row_high {
+            for col in col_low..col_high {
+                rise.0 = rise.0.max((self.height(col + 1, row) - self.height(col, row)).abs());
+            }
+        }
+        for col in col_low..=col_high {
+            for row in row_low..row_high {
+                rise.1 = rise.1.max((self.height(col, row + 1) - self.height(col, row)).abs());
+            }
+        }
+        return rise.0.hypot(rise.1) / self.cell_size;
+    }
+
+    /// Most the clearance can drop between states `step` apart around `x`,
+    /// `y`, where the terrain rises by its slope and the vehicle sinks by
+    /// `climb`, the sine of its steepest pitch, over half a step
+    fn clearance_margin(&self, x: f64, y: f64, step: f64, climb: f64) -> f64 {
+        return 0.5 * step * (self.max_slope_around(x, y, 0.5 * step) + climb);
+    }
+}
+
+/// Connects waypoints with 3D Dubins maneuvers that keep a minimum clearance
+/// above a heightmap
+///
+/// A leg dipping below `clearance` is split at its lowest point by a new
+/// waypoint `clearance` above the highest terrain within `rhomin` of it,
+/// flying level where the pitch limits allow. Splitting repeats on the new
+/// legs until all legs clear the terrain or `max_waypoints` were inserted.
+#[derive(Debug, Copy, Clone)]
+pub struct TerrainFollower {
+    pub rhomin: f64,
+    pub pitchlims: (f64, f64),
+    /// Minimum height above the terrain
+    pub clearance: f64,
+    /// Most intermediate waypoints inserted over the whole path
+    pub max_waypoints: usize
+}
+
+impl TerrainFollower {
+    /// Planner inserting up to 64 intermediate waypoints
+    ///
+    /// Panics if the limits are invalid, see `try_new` for a fallible variant.
+    pub fn new(rhomin: f64, pitchlims: (f64, f64), clearance: f64) -> Self {
+        match TerrainFollower::try_new(rhomin, pitchlims, clearance) {
+            Ok(planner) => planner,
+            Err(err) => panic!("unable to construct terrain follower: {}", err)
+        }
+    }
+
+    /// Planner inserting up to 64 intermediate waypoints, returning an error
+    /// if the radius, the pitch limits or the clearance are invalid
+    pub fn try_new(rhomin: f64, pitchlims: (f64, f64), clearance: f64) -> Result<Self, DubinsError> {
+        validate_radius(rhomin)?;
+        validate_pitch_limits(pitchlims)?;
+        validate_clearance(clearance)?;
+        return Ok(TerrainFollower{rhomin: rhomin, pitchlims: pitchlims, clearance: clearance, max_waypoints: 64});
+    }
+
+    /// Plan a path through `waypoints` keeping the clearance above `terrain`
+    ///
+    /// Every waypoint must itself keep the clearance. Returns `NoPathFound`
+    /// if a leg leaves the heightmap or the waypoint budget runs out.
+    pub fn solve(&self, terrain: &Heightmap, waypoints: &[State]) -> Result<DubinsPath3D, DubinsError> {
+        validate_clearance(self.clearance)?;
+        if waypoints.len() < 2 {
+            return Err(DubinsError::TooFewWaypoints(waypoints.len()));
+        }
+        for (index, waypoint) in waypoints.iter().enumerate() {
+            match terrain.clearance(waypoint) {
+                Some(clearance) if clearance >= self.clearance => {},
+                _ => return Err(DubinsError::InsufficientClearance(index))
+            }
+        }
+
+        let step = 0.25 * terrain.cell_size();
+        let climb = steepest_climb(self.pitchlims);
+        let mut legs: Vec<DubinsManeuver3D> = Vec::new();
+        let mut inserted = 0;
+        for pair in waypoints.windows(2) {
+            // Stack of legs still to plan, the next one on top
+            let mut pending = vec![(pair[0], pair[1])];
+            while let Some((qi, qf)) = pending.pop() {
+                let leg = DubinsManeuver3D::try_new(qi, qf, self.rhomin, self.pitchlims)?;
+                let (s, clearance) = leg.lowest_clearance(terrain).ok_or(DubinsError::NoPathFound)?;
+                if clearance >= self.clearance {
+                    legs.push(leg);
+                    continue;
+                }
+                if inserted >= self.max_waypoints {
+                    return Err(DubinsError::NoPathFound);
+                }
+                inserted += 1;
+
+                // Raised by the sampling margin of `lowest_clearance`, so
+                // that the legs through it are not reported too low again
+                let q = leg.state_at(s);
+                let margin = 0.5 * step * (terrain.max_slope_around(q.x, q.y, self.rhomin) + climb);
+                let waypoint = State {
+                    x: q.x,
+                    y: q.y,
+                    z: terrain.max_height_around(q.x, q.y, self.rhomin) + self.clearance + margin,
+                    yaw: q.yaw,
+                    pitch: 0.0_f64.clamp(self.pitchlims.0, self.pitchlims.1)
+                };
+                pending.push((waypoint, qf));
+                pending.push((qi, waypoint));
+            }
+        }
+        return DubinsPath3D::from_legs(legs);
+    }
+}
+
+impl DubinsManeuver3D {
+    /// Arc length and a lower bound on the height of the lowest point above
+    /// `terrain`, `None` if the maneuver leaves the heightmap
+    ///
+    /// States are checked a quarter of the cell size apart. Between them
+    /// the terrain may peak at grid points the samples miss, so the
+    /// clearance of each state is reduced by the most the local slope and
+    /// the steepest pitch can take off within half a step. Panics if the
+    /// maneuver holds no path.
+    pub fn lowest_clearance(&self, terrain: &Heightmap) -> Option<(f64, f64)> {
+        let step = 0.25 * terrain.cell_size();
+        let climb = steepest_climb(self.pitchlims());
+        let count = (self.length() / step).ceil() as usize;
+        let mut lowest = (0.0, core::f64::INFINITY);
+        for i in 0..=count {
+            let s = (i as f64 * step).min(self.length());
+            let q = self.state_at(s);
+            let clearance = terrain.clearance(&q)? - terrain.clearance_margin(q.x, q.y, step, climb);
+            if clearance < lowest.1 {
+                lowest = (s, clearance);
+            }
+        }
+        return Some(lowest);
+    }
+
+    /// Whether the maneuver stays at least `clearance` above `terrain`, see
+    /// `lowest_clearance`
+    pub fn keeps_clearance(&self, terrain: &Heightmap, clearance: f64) -> bool {
+        return matches!(self.lowest_clearance(terrain), Some((_, lowest)) if lowest >= clearance);
+    }
+}
+
+impl DubinsPath3D {
+    /// Arc length along the whole path and height of the lowest point above
+    /// `terrain`, `None` if the path leaves the heightmap
+    pub fn lowest_clearance(&self, terrain: &Heightmap) -> Option<(f64, f64)> {
+        let mut lowest = (0.0, core::f64::INFINITY);
+        let mut offset = 0.0;
+        for leg in self.legs() {
+            let (s, clearance) = leg.lowest_clearance(terrain)?;
+            if clearance < lowest.1 {
+                lowest = (offset + s, clearance);
+            }
+            offset += leg.length();
+        }
+        return Some(lowest);
+    }
+
+    /// Whether the whole path stays at least `clearance` above `terrain`
+    pub fn keeps_clearance(&self, terrain: &Heightmap, clearance: f64) -> bool {
+        return matches!(self.lowest_clearance(terrain), Some((_, lowest)) if lowest >= clearance);
+    }
+}
+
+/// Sine of the steepest pitch allowed by `pitchlims`
+fn steepest_climb(pitchlims: (f64, f64)) -> f64 {
+    return pitchlims.0.abs().max(pitchlims.1.abs()).sin();
+}
+
+/// Count given as a header value, rejecting fractions and negative numbers
+fn parse_count(value: f64) -> Result<usize, DubinsError> {
+    if value < 0.0 || value.fract() != 0.0 {
+        return Err(DubinsError::InvalidHeightmap("grid size must be a whole number"));
+    }
+    return Ok(value as usize);
+}
+
+/// Reverse the row order of a row-major grid
+fn north_first_to_south_first(values: &[f64], cols: usize) -> Vec<f64> {
+    if cols == 0 {
+        return Vec::new();
+    }
+    return values.chunks(cols).rev().flatten().copied().collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lowest_clearance_bounds_peaks_between_samples() {
+        // Single peak at grid point (5, 5), the level maneuver passes right
+        // over it halfway between two samples
+        let mut heights = vec![0.0; 11 * 11];
+        heights[5 * 11 + 5] = 50.0;
+        let terrain = Heightmap::new((0.0, 0.0), 10.0, 11, 11, heights).unwrap();
+        let qi = State{x: 48.75, y: 50.0, z: 80.0, yaw: 0.0, pitch: 0.0};
+        let qf = State{x: 98.75, y: 50.0, z: 80.0, yaw: 0.0, pitch: 0.0};
+        let maneuver = DubinsManeuver3D::new(qi, qf, 20.0, (-0.3, 0.3));
+
+        let (_, lowest) = maneuver.lowest_clearance(&terrain).unwrap();
+        assert!(lowest <= 30.0);
+        assert!(!maneuver.keeps_clearance(&terrain, 30.5));
+    }
+
+    fn raw_grid(values: &[f32]) -> Vec<u8> {
+        return values.iter().flat_map(|value| value.to_le_bytes()).collect();
+    }
+
+    #[test]
+    fn raw_grid_rejects_no_data_cells() {
+        let bytes = raw_grid(&[0.0, 1.0, -9999.0, 3.0]);
+        assert!(Heightmap::from_raw_f32(&bytes, 2, 2, (0.0, 0.0), 10.0, Some(-9999.0)).is_err());
+
+        let bytes = raw_grid(&[0.0, 1.0, 2.0, 3.0]);
+        let terrain = Heightmap::from_raw_f32(&bytes, 2, 2, (0.0, 0.0), 10.0, Some(-9999.0)).unwrap();
+        assert_eq!(terrain.height(0, 0), 2.0);
+        assert_eq!(terrain.height(1, 1), 1.0);
+    }
+
+    #[test]
+    fn raw_grid_rejects_overflowing_size() {
+        let result = Heightmap::from_raw_f32(&[], usize::MAX / 2, 3, (0.0, 0.0), 10.0, None);
+        assert_eq!(result, Err(DubinsError::InvalidHeightmap("grid size overflows")));
+    }
+
+    #[test]
+    fn follower_rejects_invalid_clearance() {
+        for clearance in [-1.0, core::f64::NAN, core::f64::INFINITY] {
+            assert!(matches!(TerrainFollower::try_new(40.0, (-0.3, 0.3), clearance), Err(DubinsError::InvalidClearance(_))));
+        }
+        assert!(TerrainFollower::try_new(40.0, (-0.3, 0.3), 0.0).is_ok());
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let terrain = Heightmap::new((0.0, 0.0), 10.0, 2, 2, vec![0.0, 1.0, 2.0, 3.0]).unwrap();
+        let json = serde_json::to_string(&terrain).unwrap();
+        assert_eq!(serde_json::from_str::<Heightmap>(&json).unwrap(), terrain);
+    }
+
+    #[test]
+    fn rejects_mismatched_grid() {
+        let json = r#"{"origin": [0.0, 0.0], "cell_size": 10.0, "cols": 3, "rows": 2, "heights": [0.0, 1.0, 2.0, 3.0]}"#;
+        assert!(serde_json::from_str::<Heightmap>(json).is_err());
+    }
+}
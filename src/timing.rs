@@ -0,0 +1,331 @@
+use crate::State;
+use crate::error::DubinsError;
+use crate::dubins3d::{DubinsManeuver3D, get_rates_at};
+
+/// Speed along a maneuver, accelerating from the initial speed to the cruise
+/// speed and braking to the final speed within the acceleration limits
+///
+/// If the maneuver is too short to reach the cruise speed the profile peaks
+/// below it. Infinite limits change the speed instantly.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpeedProfile {
+    pub initial_speed: f64,
+    pub cruise_speed: f64,
+    pub final_speed: f64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::infinite"))]
+    pub max_acceleration: f64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::infinite"))]
+    pub max_deceleration: f64
+}
+
+impl SpeedProfile {
+    /// Trapezoidal profile between the given speeds
+    pub fn new(initial_speed: f64, cruise_speed: f64, final_speed: f64, max_acceleration: f64, max_deceleration: f64) -> Self {
+        return SpeedProfile {
+            initial_speed: initial_speed,
+            cruise_speed: cruise_speed,
+            final_speed: final_speed,
+            max_acceleration: max_acceleration,
+            max_deceleration: max_deceleration
+        };
+    }
+
+    /// The same speed along the whole maneuver
+    pub fn constant(speed: f64) -> Self {
+        return SpeedProfile::new(speed, speed, speed, core::f64::INFINITY, core::f64::INFINITY);
+    }
+
+    fn validate(&self) -> Result<(), DubinsError> {
+        if !(self.cruise_speed > 0.0 && self.cruise_speed.is_finite()) {
+            return Err(DubinsError::InvalidSpeedProfile("cruise speed must be positive"));
+        }
+        if !(self.initial_speed >= 0.0 && self.initial_speed <= self.cruise_speed) {
+            return Err(DubinsError::InvalidSpeedProfile("initial speed must lie between zero and the cruise speed"));
+        }
+        if !(self.final_speed >= 0.0 && self.final_speed <= self.cruise_speed) {
+            return Err(DubinsError::InvalidSpeedProfile("final speed must lie between zero and the cruise speed"));
+        }
+        if !(self.max_acceleration > 0.0 && self.max_deceleration > 0.0) {
+            return Err(DubinsError::InvalidSpeedProfile("acceleration limits must be positive"));
+        }
+        return Ok(());
+    }
+}
+
+/// State along a maneuver together with its time stamp and rates
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimedState {
+    pub t: f64,
+    pub state: State,
+    pub speed: f64,
+    pub yaw_rate: f64,
+    pub pitch_rate: f64
+}
+
+impl TimedState {
+    /// Velocity vector in the frame of the state
+    pub fn velocity(&self) -> (f64, f64, f64) {
+        let q = &self.state;
+        return (
+            self.speed * q.pitch.cos() * q.yaw.cos(),
+            self.speed * q.pitch.cos() * q.yaw.sin(),
+            self.speed * q.pitch.sin()
+        );
+    }
+}
+
+/// A 3D maneuver flown with a speed profile, maps between time and arc length
+///
+/// Created by `DubinsManeuver3D::timed`.
+#[derive(Debug, Clone)]
+pub struct TimedManeuver<'a> {
+    maneuver: &'a DubinsManeuver3D,
+    profile: SpeedProfile,
+    peak_speed: f64,
+    accel_length: f64,
+    decel_length: f64,
+    accel_time: f64,
+    cruise_time: f64,
+    duration: f64
+}
+
+impl<'a> TimedManeuver<'a> {
+    /// Fit the speed profile to the length of the maneuver
+    pub fn new(maneuver: &'a DubinsManeuver3D, profile: SpeedProfile) -> Result<Self, DubinsError> {
+        profile.validate()?;
+        let length = maneuver.length();
+        if !(length >= 0.0 && length.is_finite()) {
+            return Err(DubinsError::InvalidSpeedProfile("maneuver has no finite length"));
+        }
+
+        let v0 = profile.initial_speed;
+        let v1 = profile.final_speed;
+        let a = profile.max_acceleration;
+        let d = profile.max_deceleration;
+
+        let mut peak_speed = profile.cruise_speed;
+        let mut accel_length = (peak_speed * peak_speed - v0 * v0) / (2.0 * a);
+        let mut decel_length = (peak_speed * peak_speed - v1 * v1) / (2.0 * d);
+        if accel_length + decel_length > length {
+            // Accelerate until braking has to begin
+            let peak_squared = (length + v0 * v0 / (2.0 * a) + v1 * v1 / (2.0 * d)) / (0.5 / a + 0.5 / d);
+            peak_speed = peak_squared.sqrt();
+            if peak_speed < v0 || peak_speed < v1 {
+                return Err(DubinsError::InvalidSpeedProfile("speed change does not fit within the maneuver length"));
+            }
+            accel_length = (peak_squared - v0 * v0) / (2.0 * a);
+            decel_length = (peak_squared - v1 * v1) / (2.0 * d);
+        }
+
+        let cruise_length = (length - accel_length - decel_length).max(0.0);
+        let accel_time = (peak_speed - v0) / a;
+        let cruise_time = if cruise_length > 0.0 { cruise_length / peak_speed } else { 0.0 };
+        let decel_time = (peak_speed - v1) / d;
+
+        return Ok(TimedManeuver {
+            maneuver: maneuver,
+            profile: profile,
+            peak_speed: peak_speed,
+            accel_length: accel_length,
+            decel_length: decel_length,
+            accel_time: accel_time,
+            cruise_time: cruise_time,
+            duration: accel_time + cruise_time + decel_time
+        });
+    }
+
+    /// Maneuver being timed
+    pub fn maneuver(&self) -> &'a DubinsManeuver3D {
+        return self.maneuver;
+    }
+
+    /// Speed profile the maneuver is flown with
+    pub fn profile(&self) -> SpeedProfile {
+        return self.profile;
+    }
+
+    /// Highest speed reached, below the cruise speed on short maneuvers
+    pub fn peak_speed(&self) -> f64 {
+        return self.peak_speed;
+    }
+
+    /// Time needed to fly the whole maneuver
+    pub fn duration(&self) -> f64 {
+        return self.duration;
+    }
+
+    /// Speed at arc length `s`, clamped to the maneuver
+    pub fn speed_at(&self, s: f64) -> f64 {
+        let length = self.maneuver.length();
+        let s = s.max(0.0).min(length);
+        if s < self.accel_length {
+            let v0 = self.profile.initial_speed;
+            return (v0 * v0 + 2.0 * self.profile.max_acceleration * s).sqrt();
+        }
+        if s <= length - self.decel_length {
+            return self.peak_speed;
+        }
+        let v1 = self.profile.final_speed;
+        return (v1 * v1 + 2.0 * self.profile.max_deceleration * (length - s)).sqrt();
+    }
+
+    /// Time at which arc length `s` is reached, clamped to the maneuver
+    pub fn time_at(&self, s: f64) -> f64 {
+        let length = self.maneuver.length();
+        let s = s.max(0.0).min(length);
+        if s < self.accel_length {
+            return (self.speed_at(s) - self.profile.initial_speed) / self.profile.max_acceleration;
+        }
+        if s <= length - self.decel_length {
+            return self.accel_time + (s - self.accel_length) / self.peak_speed;
+        }
+        return self.accel_time + self.cruise_time + (self.peak_speed - self.speed_at(s)) / self.profile.max_deceleration;
+    }
+
+    /// Arc length reached at time `t`, clamped to the maneuver
+    pub fn arc_length_at(&self, t: f64) -> f64 {
+        let length = self.maneuver.length();
+        if t <= 0.0 {
+            return 0.0;
+        }
+        if t >= self.duration {
+            return length;
+        }
+        if t < self.accel_time {
+            return self.profile.initial_speed * t + 0.5 * self.profile.max_acceleration * t * t;
+        }
+        if t < self.accel_time + self.cruise_time {
+            return self.accel_length + self.peak_speed * (t - self.accel_time);
+        }
+        let tau = t - self.accel_time - self.cruise_time;
+        let s = length - self.decel_length + self.peak_speed * tau - 0.5 * self.profile.max_deceleration * tau * tau;
+        return s.min(length);
+    }
+
+    /// Timed state at time `t`, clamped to the maneuver
+    ///
+    /// Panics if the maneuver holds no path.
+    pub fn state_at_time(&self, t: f64) -> TimedState {
+        let t = t.max(0.0).min(self.duration);
+        return self.timed_state(t, self.arc_length_at(t));
+    }
+
+    /// Timed states spaced `dt` apart in time, the last item is exactly the
+    /// final state
+    ///
+    /// Panics if `dt` is not positive or the maneuver holds no path.
+    pub fn sample_by_time(&self, dt: f64) -> Vec<TimedState> {
+        assert!(dt > 0.0, "sampling interval must be positive, got {}", dt);
+        let count = (self.duration / dt).ceil() as usize;
+        let mut points: Vec<TimedState> = Vec::with_capacity(count + 1);
+        for i in 0..count {
+            points.push(self.state_at_time(dt * i as f64));
+        }
+        points.push(self.timed_state(self.duration, self.maneuver.length()));
+        return points;
+    }
+
+    fn timed_state(&self, t: f64, s: f64) -> TimedState {
+        let speed = self.speed_at(s);
+        let (yaw_rate, pitch_rate) = match (self.maneuver.lateral(), self.maneuver.longitudinal()) {
+            (Some(dlat), Some(dlon)) => get_rates_at(dlat, dlon, s),
+            _ => panic!("maneuver holds no path")
+        };
+        return TimedState {
+            t: t,
+            state: self.maneuver.state_at(s),
+            speed: speed,
+            yaw_rate: yaw_rate * speed,
+            pitch_rate: pitch_rate * speed
+        };
+    }
+}
+
+impl DubinsManeuver3D {
+    /// Fly the maneuver with a speed profile to obtain time stamped states
+    pub fn timed(&self, profile: SpeedProfile) -> Result<TimedManeuver<'_>, DubinsError> {
+        return TimedManeuver::new(self, profile);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn straight(length: f64) -> DubinsManeuver3D {
+        let qi = State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+        let qf = State{x: length, ..qi};
+        return DubinsManeuver3D::new(qi, qf, 40.0, (-0.3, 0.3));
+    }
+
+    #[test]
+    fn constant_speed_duration() {
+        let maneuver = straight(400.0);
+        let timed = maneuver.timed(SpeedProfile::constant(16.0)).unwrap();
+        assert!((timed.duration() - 25.0).abs() < 1e-9);
+        assert!((timed.speed_at(123.0) - 16.0).abs() < 1e-12);
+        assert!((timed.peak_speed() - 16.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn trapezoid_duration() {
+        // 100 m accelerating for 10 s, 250 m cruising for 12.5 s and 50 m braking for 5 s
+        let maneuver = straight(400.0);
+        let timed = maneuver.timed(SpeedProfile::new(0.0, 20.0, 0.0, 2.0, 4.0)).unwrap();
+        assert!((timed.peak_speed() - 20.0).abs() < 1e-12);
+        assert!((timed.duration() - 27.5).abs() < 1e-9);
+        assert!((timed.speed_at(0.0)).abs() < 1e-12);
+        assert!((timed.speed_at(200.0) - 20.0).abs() < 1e-12);
+        assert!((timed.speed_at(400.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn triangle_never_reaches_cruise() {
+        // 200 m accelerating at 1 m/s² to 20 m/s, then 200 m braking
+        let maneuver = straight(400.0);
+        let timed = maneuver.timed(SpeedProfile::new(0.0, 50.0, 0.0, 1.0, 1.0)).unwrap();
+        assert!((timed.peak_speed() - 20.0).abs() < 1e-9);
+        assert!((timed.duration() - 40.0).abs() < 1e-9);
+        assert!((timed.arc_length_at(20.0) - 200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn time_and_arc_length_round_trip() {
+        let maneuver = straight(400.0);
+        let profiles = [
+            SpeedProfile::constant(16.0),
+            SpeedProfile::new(5.0, 20.0, 8.0, 2.0, 4.0),
+            SpeedProfile::new(0.0, 50.0, 0.0, 1.0, 3.0)
+        ];
+        for profile in profiles {
+            let timed = maneuver.timed(profile).unwrap();
+            for i in 0..=40 {
+                let s = 10.0 * i as f64;
+                assert!((timed.arc_length_at(timed.time_at(s)) - s).abs() < 1e-9, "{:?} at {}", profile, s);
+            }
+            assert!((timed.time_at(400.0) - timed.duration()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn rejects_speed_change_longer_than_the_maneuver() {
+        let maneuver = straight(10.0);
+        let result = maneuver.timed(SpeedProfile::new(0.0, 20.0, 20.0, 1.0, 1.0));
+        assert!(matches!(result, Err(DubinsError::InvalidSpeedProfile(_))));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn instant_speed_changes_round_trip() {
+        let profile = SpeedProfile::constant(16.0);
+        let json = serde_json::to_string(&profile).unwrap();
+        let restored: SpeedProfile = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, profile);
+    }
+}
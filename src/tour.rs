@@ -0,0 +1,342 @@
+use std::time::{Duration, Instant};
+
+use crate::State;
+use crate::error::DubinsError;
+use crate::dubins3d::DubinsManeuver3D;
+use crate::headings::HeadingOptimizer;
+use crate::path::DubinsPath3D;
+
+/// Largest number of targets `TourMode::Auto` solves exactly
+const EXACT_TARGET_LIMIT: usize = 8;
+
+/// How the visiting order of a tour is searched
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TourMode {
+    /// Branch and bound over all orders, pruned with `get_lower_bound`
+    Exact,
+    /// Nearest neighbour construction improved by 2-opt on straight line distances
+    Heuristic,
+    /// `Exact` for small instances, `Heuristic` otherwise
+    Auto
+}
+
+/// Visiting order and path found by `TourPlanner`
+pub struct Tour {
+    /// Indices into the targets in visiting order
+    pub order: Vec<usize>,
+    pub path: DubinsPath3D,
+    /// Whether the exact search finished within the time budget
+    pub optimal: bool
+}
+
+/// Find an order and headings for visiting unordered 3D targets from a
+/// start state with minimal total 3D Dubins length
+///
+/// Headings at the targets are chosen from the grid of a `HeadingOptimizer`
+/// with `yaw_samples` and `pitch_samples`, exact results are optimal with
+/// respect to that grid.
+#[derive(Debug, Copy, Clone)]
+pub struct TourPlanner {
+    pub rhomin: f64,
+    pub pitchlims: (f64, f64),
+    pub yaw_samples: usize,
+    pub pitch_samples: usize,
+    pub time_budget: Duration,
+    pub mode: TourMode
+}
+
+impl TourPlanner {
+    /// Planner with 8 level headings per target and a budget of ten seconds
+    pub fn new(rhomin: f64, pitchlims: (f64, f64)) -> Self {
+        return TourPlanner {
+            rhomin: rhomin,
+            pitchlims: pitchlims,
+            yaw_samples: 8,
+            pitch_samples: 0,
+            time_budget: Duration::from_secs(10),
+            mode: TourMode::Auto
+        };
+    }
+
+    /// Plan a tour starting at `start` and visiting every target once
+    ///
+    /// The heuristic tour is planned first. If the exact search runs out of
+    /// time, even while precomputing its bounds, the best tour found so far
+    /// is returned and is not marked optimal.
+    pub fn solve(&self, start: State, targets: &[(f64, f64, f64)]) -> Result<Tour, DubinsError> {
+        if targets.is_empty() {
+            return Err(DubinsError::NoTargets);
+        }
+        let deadline = Instant::now() + self.time_budget;
+
+        let mut order = nearest_neighbour_order(start, targets);
+        improve_two_opt(start, targets, &mut order, deadline);
+        let path = self.evaluate(start, targets, &order)?;
+        let mut tour = Tour{order: order, path: path, optimal: false};
+
+        let exact = match self.mode {
+            TourMode::Exact => true,
+            TourMode::Heuristic => false,
+            TourMode::Auto => targets.len() <= EXACT_TARGET_LIMIT
+        };
+        if exact {
+            if let Some(mut search) = BranchAndBound::new(self, start, targets, deadline) {
+                search.run(&mut tour)?;
+                tour.optimal = !search.timed_out;
+            }
+        }
+        return Ok(tour);
+    }
+
+    fn heading_optimizer(&self) -> HeadingOptimizer {
+        return HeadingOptimizer {
+            rhomin: self.rhomin,
+            pitchlims: self.pitchlims,
+            yaw_samples: self.yaw_samples,
+            pitch_samples: self.pitch_samples
+        };
+    }
+
+    /// Shortest path visiting the targets in the given order
+    fn evaluate(&self, start: State, targets: &[(f64, f64, f64)], order: &[usize]) -> Result<DubinsPath3D, DubinsError> {
+        let mut points: Vec<(f64, f64, f64)> = Vec::with_capacity(order.len() + 1);
+        points.push((start.x, start.y, start.z));
+        points.extend(order.iter().map(|i| targets[*i]));
+        return self.heading_optimizer().solve(&points, Some((start.yaw, start.pitch)), None);
+    }
+}
+
+/// Depth first search over visiting orders, each partial order is bounded by
+/// the shortest chain of lower bound maneuvers through its targets
+struct BranchAndBound<'a> {
+    planner: &'a TourPlanner,
+    start: State,
+    targets: &'a [(f64, f64, f64)],
+    deadline: Instant,
+    timed_out: bool,
+    candidates: usize,
+    /// Lower bound lengths from the start to every candidate state
+    from_start: Vec<f64>,
+    /// Lower bound lengths between candidate states, indexed by
+    /// `(target * candidates + candidate)` for both ends
+    between: Vec<f64>,
+    /// Smallest lower bound of any maneuver entering each target
+    min_entry: Vec<f64>
+}
+
+impl<'a> BranchAndBound<'a> {
+    /// Precompute the lower bounds, `None` if the deadline passes first
+    ///
+    /// Expects inputs validated by planning the heuristic tour.
+    fn new(planner: &'a TourPlanner, start: State, targets: &'a [(f64, f64, f64)], deadline: Instant) -> Option<Self> {
+        let angles = planner.heading_optimizer().candidates();
+        let states: Vec<State> = targets.iter().flat_map(|point| {
+            angles.iter().map(move |angle| State{x: point.0, y: point.1, z: point.2, yaw: angle.0, pitch: angle.1})
+        }).collect();
+
+        let lower_bound = |qi: State, qf: State| {
+            return DubinsManeuver3D::solve_lower_bound(qi, qf, planner.rhomin, planner.pitchlims).length();
+        };
+
+        let from_start: Vec<f64> = states.iter().map(|qf| lower_bound(start, *qf)).collect();
+        let mut between: Vec<f64> = Vec::with_capacity(states.len() * states.len());
+        for qi in states.iter() {
+            if Instant::now() >= deadline {
+                return None;
+            }
+            between.extend(states.iter().map(|qf| lower_bound(*qi, *qf)));
+        }
+
+        let candidates = angles.len();
+        let mut min_entry: Vec<f64> = vec![core::f64::INFINITY; targets.len()];
+        for (target, entry) in min_entry.iter_mut().enumerate() {
+            for b in 0..candidates {
+                let j = target * candidates + b;
+                *entry = entry.min(from_start[j]);
+                for i in 0..states.len() {
+                    if i / candidates != target {
+                        *entry = entry.min(between[i * states.len() + j]);
+                    }
+                }
+            }
+        }
+
+        return Some(BranchAndBound {
+            planner: planner,
+            start: start,
+            targets: targets,
+            deadline: deadline,
+            timed_out: false,
+            candidates: candidates,
+            from_start: from_start,
+            between: between,
+            min_entry: min_entry
+        });
+    }
+
+    fn run(&mut self, best: &mut Tour) -> Result<(), DubinsError> {
+        let mut order: Vec<usize> = Vec::with_capacity(self.targets.len());
+        let mut visited: Vec<bool> = vec![false; self.targets.len()];
+        let remaining: f64 = self.min_entry.iter().sum();
+        return self.branch(&mut order, &mut visited, &[], remaining, best);
+    }
+
+    fn branch(&mut self, order: &mut Vec<usize>, visited: &mut [bool], costs: &[f64], remaining: f64, best: &mut Tour) -> Result<(), DubinsError> {
+        if Instant::now() >= self.deadline {
+            self.timed_out = true;
+            return Ok(());
+        }
+
+        let mut children: Vec<(f64, usize, Vec<f64>)> = Vec::new();
+        for (target, done) in visited.iter().enumerate() {
+            if *done {
+                continue;
+            }
+            let next = self.extend(order.last().copied(), costs, target);
+            let bound = min(&next) + remaining - self.min_entry[target];
+            if bound < best.path.length() {
+                children.push((bound, target, next));
+            }
+        }
+        children.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        for (bound, target, next) in children {
+            if bound >= best.path.length() {
+                break;
+            }
+            order.push(target);
+            visited[target] = true;
+            if order.len() == self.targets.len() {
+                if let Ok(path) = self.planner.evaluate(self.start, self.targets, order) {
+                    if path.length() < best.path.length() {
+                        best.order = order.clone();
+                        best.path = path;
+                    }
+                }
+            }
+            else {
+                self.branch(order, visited, &next, remaining - self.min_entry[target], best)?;
+            }
+            order.pop();
+            visited[target] = false;
+            if self.timed_out {
+                break;
+            }
+        }
+        return Ok(());
+    }
+
+    /// Lower bound cost of reaching each candidate state of `target` after
+    /// the partial order ending at `last`
+    fn extend(&self, last: Option<usize>, costs: &[f64], target: usize) -> Vec<f64> {
+        let n = self.targets.len() * self.candidates;
+        return (0..self.candidates).map(|b| {
+            let j = target * self.candidates + b;
+            match last {
+                None => self.from_start[j],
+                Some(previous) => (0..self.candidates).map(|a| {
+                    let i = previous * self.candidates + a;
+                    costs[a] + self.between[i * n + j]
+                }).fold(core::f64::INFINITY, f64::min)
+            }
+        }).collect();
+    }
+}
+
+fn min(values: &[f64]) -> f64 {
+    return values.iter().copied().fold(core::f64::INFINITY, f64::min);
+}
+
+fn distance(a: (f64, f64, f64), b: (f64, f64, f64)) -> f64 {
+    return ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2) + (b.2 - a.2).powi(2)).sqrt();
+}
+
+fn nearest_neighbour_order(start: State, targets: &[(f64, f64, f64)]) -> Vec<usize> {
+    let mut order: Vec<usize> = Vec::with_capacity(targets.len());
+    let mut visited: Vec<bool> = vec![false; targets.len()];
+    let mut current = (start.x, start.y, start.z);
+    for _ in 0..targets.len() {
+        let mut next = 0;
+        let mut next_distance = core::f64::INFINITY;
+        for (i, target) in targets.iter().enumerate() {
+            let d = distance(current, *target);
+            if !visited[i] && d < next_distance {
+                next = i;
+                next_distance = d;
+            }
+        }
+        visited[next] = true;
+        order.push(next);
+        current = targets[next];
+    }
+    return order;
+}
+
+/// Reverse sub-sequences of the open tour while that shortens its straight
+/// line length
+fn improve_two_opt(start: State, targets: &[(f64, f64, f64)], order: &mut [usize], deadline: Instant) {
+    let point = |order: &[usize], i: usize| -> (f64, f64, f64) {
+        if i == 0 { (start.x, start.y, start.z) } else { targets[order[i - 1]] }
+    };
+    let n = order.len();
+    let mut improved = true;
+    while improved && Instant::now() < deadline {
+        improved = false;
+        for i in 0..n {
+            for j in (i + 1)..n {
+                // Replace edges (i, i+1) and (j+1, j+2) in the sequence that
+                // starts with the start state
+                let a = point(order, i);
+                let b = point(order, i + 1);
+                let c = point(order, j + 1);
+                let before = distance(a, b) + if j + 1 < n { distance(c, point(order, j + 2)) } else { 0.0 };
+                let after = distance(a, c) + if j + 1 < n { distance(b, point(order, j + 2)) } else { 0.0 };
+                if after < before - 1e-9 {
+                    order[i..=j].reverse();
+                    improved = true;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: State = State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+    const TARGETS: [(f64, f64, f64); 5] = [
+        (300.0, 0.0, 10.0), (300.0, 300.0, 20.0), (0.0, 300.0, 0.0), (-200.0, 100.0, 30.0), (150.0, 150.0, 10.0)
+    ];
+
+    #[test]
+    fn exact_tour_is_no_longer_than_heuristic() {
+        let mut planner = TourPlanner::new(40.0, (-0.3, 0.3));
+        planner.mode = TourMode::Heuristic;
+        let heuristic = planner.solve(START, &TARGETS).unwrap();
+        planner.mode = TourMode::Exact;
+        let exact = planner.solve(START, &TARGETS).unwrap();
+
+        assert!(exact.optimal);
+        assert!(exact.path.length() <= heuristic.path.length() + 1e-9);
+        let mut visited = exact.order.clone();
+        visited.sort();
+        assert_eq!(visited, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn exhausted_budget_keeps_the_heuristic_tour() {
+        let mut planner = TourPlanner::new(40.0, (-0.3, 0.3));
+        planner.mode = TourMode::Exact;
+        planner.time_budget = Duration::ZERO;
+        let tour = planner.solve(START, &TARGETS).unwrap();
+        assert!(!tour.optimal);
+        assert_eq!(tour.order.len(), TARGETS.len());
+    }
+
+    #[test]
+    fn empty_targets_are_rejected() {
+        let planner = TourPlanner::new(40.0, (-0.3, 0.3));
+        assert_eq!(planner.solve(START, &[]).err(), Some(DubinsError::NoTargets));
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{PI, State, mod_pi};
+use crate::dubins2d::State2D;
+use crate::error::{DubinsError, Endpoint};
+
+/// Check a planning problem before it is handed to the solver
+///
+/// Rejects non-finite states, non-positive or non-finite radii, empty or
+/// vertical pitch ranges and endpoint pitches outside of `pitchlims`.
+pub fn validate(qi: &State, qf: &State, rhomin: f64, pitchlims: (f64, f64)) -> Result<(), DubinsError> {
+    validate_radius(rhomin)?;
+    validate_pitch_limits(pitchlims)?;
+    validate_state(qi, Endpoint::Start)?;
+    validate_state(qf, Endpoint::Goal)?;
+    validate_pitch(qi, Endpoint::Start, pitchlims)?;
+    validate_pitch(qf, Endpoint::Goal, pitchlims)?;
+    return Ok(());
+}
+
+pub(crate) fn validate_radius(rhomin: f64) -> Result<(), DubinsError> {
+    if !rhomin.is_finite() || rhomin <= 0.0 {
+        return Err(DubinsError::NonPositiveRadius(rhomin));
+    }
+    return Ok(());
+}
+
+pub(crate) fn validate_pitch_limits(pitchlims: (f64, f64)) -> Result<(), DubinsError> {
+    let (min, max) = pitchlims;
+    if min.is_nan() || max.is_nan() || min >= max || min <= -PI / 2.0 || max >= PI / 2.0 {
+        return Err(DubinsError::InfeasiblePitchLimits(min, max));
+    }
+    return Ok(());
+}
+
+pub(crate) fn validate_state(q: &State, endpoint: Endpoint) -> Result<(), DubinsError> {
+    let fields = [("x", q.x), ("y", q.y), ("z", q.z), ("yaw", q.yaw), ("pitch", q.pitch)];
+    for (name, value) in fields {
+        if !value.is_finite() {
+            return Err(DubinsError::NonFiniteState(endpoint, name));
+        }
+    }
+    return Ok(());
+}
+
+/// Sampled states carry their pitch wrapped into [0, 2pi), so it is compared
+/// in (-pi, pi] to accept a descent given as 2pi - x
+pub(crate) fn validate_pitch(q: &State, endpoint: Endpoint, pitchlims: (f64, f64)) -> Result<(), DubinsError> {
+    let pitch = mod_pi(q.pitch);
+    if pitch < pitchlims.0 || pitch > pitchlims.1 {
+        return Err(DubinsError::PitchOutOfLimits(endpoint, pitch));
+    }
+    return Ok(());
+}
+
+pub(crate) fn validate_altitude_limits(zlims: (f64, f64)) -> Result<(), DubinsError> {
+    let (min, max) = zlims;
+    if min.is_nan() || max.is_nan() || min >= max {
+        return Err(DubinsError::InfeasibleAltitudeLimits(min, max));
+    }
+    return Ok(());
+}
+
+pub(crate) fn validate_altitude(q: &State, endpoint: Endpoint, zlims: (f64, f64)) -> Result<(), DubinsError> {
+    if q.z < zlims.0 || q.z > zlims.1 {
+        return Err(DubinsError::AltitudeOutOfLimits(endpoint, q.z));
+    }
+    return Ok(());
+}
+
+pub(crate) fn validate_clearance(clearance: f64) -> Result<(), DubinsError> {
+    if !clearance.is_finite() || clearance < 0.0 {
+        return Err(DubinsError::InvalidClearance(clearance));
+    }
+    return Ok(());
+}
+
+pub(crate) fn validate_state2d(q: &State2D, endpoint: Endpoint) -> Result<(), DubinsError> {
+    let fields = [("x", q.x), ("y", q.y), ("yaw", q.yaw)];
+    for (name, value) in fields {
+        if !value.is_finite() {
+            return Err(DubinsError::NonFiniteState(endpoint, name));
+        }
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{PI, DubinsManeuver3D, State, mod_pi};
+
+    #[test]
+    fn sampled_descent_is_a_valid_start() {
+        let qi = State{x: 0.0, y: 0.0, z: 100.0, yaw: 0.0, pitch: 0.0};
+        let qf = State{x: 400.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+        let maneuver = DubinsManeuver3D::new(qi, qf, 40.0, (-0.3, 0.3));
+        let mid = maneuver.state_at(maneuver.length() / 2.0);
+        assert!(mid.pitch > 3.0);
+
+        let rest = DubinsManeuver3D::try_new(mid, qf, 40.0, (-0.3, 0.3)).unwrap();
+        assert!((rest.length() - maneuver.length() / 2.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn wrapped_pitch_solves_like_its_normalized_equivalent() {
+        let pitchlims = (-0.3, 0.3);
+        let wrapped = State{x: 0.0, y: 0.0, z: 100.0, yaw: 0.0, pitch: 2.0 * PI - 0.2};
+        let normalized = State{pitch: mod_pi(wrapped.pitch), ..wrapped};
+        assert!((normalized.pitch + 0.2).abs() < 1e-12);
+        let qf = State{x: 300.0, y: 150.0, z: 60.0, yaw: 1.0, pitch: 0.1};
+
+        let maneuver = DubinsManeuver3D::try_new(wrapped, qf, 40.0, pitchlims).unwrap();
+        let reference = DubinsManeuver3D::try_new(normalized, qf, 40.0, pitchlims).unwrap();
+        assert_eq!(maneuver.length(), reference.length());
+        assert_eq!(maneuver.start(), normalized);
+        for q in maneuver.samples(1.0) {
+            let pitch = mod_pi(q.pitch);
+            assert!(pitch >= pitchlims.0 - 1e-9 && pitch <= pitchlims.1 + 1e-9);
+        }
+
+        let lower = DubinsManeuver3D::get_lower_bound(wrapped, qf, 40.0, pitchlims).unwrap();
+        assert_eq!(lower.length(), DubinsManeuver3D::get_lower_bound(normalized, qf, 40.0, pitchlims).unwrap().length());
+        let upper = DubinsManeuver3D::get_upper_bound(wrapped, qf, 40.0, pitchlims).unwrap();
+        assert_eq!(upper.length(), DubinsManeuver3D::get_upper_bound(normalized, qf, 40.0, pitchlims).unwrap().length());
+    }
+}
@@ -0,0 +1,146 @@
+use crate::State;
+use crate::error::DubinsError;
+use crate::dubins3d::{DubinsManeuver3D, TurnRadii};
+
+/// Standard gravitational acceleration in m/s^2
+pub const STANDARD_GRAVITY: f64 = 9.80665;
+
+/// Performance limits of a vehicle flying at constant airspeed, as found in
+/// autopilot specifications
+///
+/// Rates are magnitudes in units of length per second, `max_sink_rate` is
+/// positive for descending. The load factor limits the normal acceleration
+/// in every direction, so pulling up, which works against gravity, needs a
+/// wider radius than a level turn and pushing over a tighter one.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VehicleConstraints {
+    pub airspeed: f64,
+    pub max_climb_rate: f64,
+    pub max_sink_rate: f64,
+    pub max_load_factor: f64,
+    pub gravity: f64
+}
+
+impl VehicleConstraints {
+    /// Constraints under standard gravity
+    pub fn new(airspeed: f64, max_climb_rate: f64, max_sink_rate: f64, max_load_factor: f64) -> Self {
+        return VehicleConstraints {
+            airspeed: airspeed,
+            max_climb_rate: max_climb_rate,
+            max_sink_rate: max_sink_rate,
+            max_load_factor: max_load_factor,
+            gravity: STANDARD_GRAVITY
+        };
+    }
+
+    /// Minimum radius of a level coordinated turn allowed by the load factor
+    /// at the airspeed
+    pub fn rhomin(&self) -> Result<f64, DubinsError> {
+        if !(self.airspeed > 0.0 && self.airspeed.is_finite()) {
+            return Err(DubinsError::InvalidVehicleConstraint("airspeed must be positive"));
+        }
+        if !(self.gravity > 0.0 && self.gravity.is_finite()) {
+            return Err(DubinsError::InvalidVehicleConstraint("gravity must be positive"));
+        }
+        if !(self.max_load_factor > 1.0 && self.max_load_factor.is_finite()) {
+            return Err(DubinsError::InvalidVehicleConstraint("load factor must be greater than one"));
+        }
+        return Ok(turn_radius(self.airspeed, self.max_load_factor, self.gravity));
+    }
+
+    /// Pitch limits given by the sink and climb rates at the airspeed
+    pub fn pitchlims(&self) -> Result<(f64, f64), DubinsError> {
+        if !(self.airspeed > 0.0 && self.airspeed.is_finite()) {
+            return Err(DubinsError::InvalidVehicleConstraint("airspeed must be positive"));
+        }
+        if !(self.max_climb_rate >= 0.0 && self.max_climb_rate < self.airspeed) {
+            return Err(DubinsError::InvalidVehicleConstraint("climb rate must be non-negative and below the airspeed"));
+        }
+        if !(self.max_sink_rate >= 0.0 && self.max_sink_rate < self.airspeed) {
+            return Err(DubinsError::InvalidVehicleConstraint("sink rate must be non-negative and below the airspeed"));
+        }
+        if self.max_climb_rate == 0.0 && self.max_sink_rate == 0.0 {
+            return Err(DubinsError::InvalidVehicleConstraint("climb and sink rate cannot both be zero"));
+        }
+        return Ok((
+            -flight_path_angle(self.max_sink_rate, self.airspeed),
+            flight_path_angle(self.max_climb_rate, self.airspeed)
+        ));
+    }
+
+    /// Minimum turning radius per direction allowed by the load factor
+    ///
+    /// Left and right are the level turn radius, up is `V^2 / (g (n - 1))`
+    /// and down is `V^2 / (g (n + 1))` for the load factor `n`.
+    pub fn turn_radii(&self) -> Result<TurnRadii, DubinsError> {
+        let level = self.rhomin()?;
+        let v2 = self.airspeed * self.airspeed;
+        return Ok(TurnRadii {
+            left: level,
+            right: level,
+            up: v2 / (self.gravity * (self.max_load_factor - 1.0)),
+            down: v2 / (self.gravity * (self.max_load_factor + 1.0))
+        });
+    }
+
+    /// Turning radius and pitch limits as expected by `DubinsManeuver3D::new`
+    ///
+    /// The radius is the pull-up radius, the widest of `turn_radii`, so that
+    /// a symmetric maneuver is flyable in every direction.
+    pub fn to_dubins_params(&self) -> Result<(f64, (f64, f64)), DubinsError> {
+        return Ok((self.turn_radii()?.up, self.pitchlims()?));
+    }
+}
+
+/// Flight path angle at which the vertical speed equals `vertical_rate`
+pub fn flight_path_angle(vertical_rate: f64, airspeed: f64) -> f64 {
+    return (vertical_rate / airspeed).asin();
+}
+
+/// Radius of a level coordinated turn flown at the given load factor
+pub fn turn_radius(airspeed: f64, load_factor: f64, gravity: f64) -> f64 {
+    return airspeed * airspeed / (gravity * (load_factor * load_factor - 1.0).sqrt());
+}
+
+impl DubinsManeuver3D {
+    /// Create a new Dubins maneuver with turn radii and pitch limits derived
+    /// from vehicle constraints
+    pub fn try_with_vehicle(qi: State, qf: State, vehicle: &VehicleConstraints) -> Result<DubinsManeuver3D, DubinsError> {
+        return DubinsManeuver3D::try_new_asymmetric(qi, qf, vehicle.turn_radii()?, vehicle.pitchlims()?);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vertical_radii_follow_the_load_factor() {
+        let vehicle = VehicleConstraints::new(20.0, 3.0, 4.0, 2.0);
+        let radii = vehicle.turn_radii().unwrap();
+        let v2 = 400.0;
+
+        assert!((radii.left - v2 / (STANDARD_GRAVITY * 3.0f64.sqrt())).abs() < 1e-9);
+        assert_eq!(radii.left, radii.right);
+        assert!((radii.up - v2 / STANDARD_GRAVITY).abs() < 1e-9);
+        assert!((radii.down - v2 / (3.0 * STANDARD_GRAVITY)).abs() < 1e-9);
+        assert!(radii.down < radii.left && radii.left < radii.up);
+        assert_eq!(vehicle.to_dubins_params().unwrap().0, radii.up);
+    }
+
+    #[test]
+    fn vehicle_maneuver_uses_the_vertical_radii() {
+        let vehicle = VehicleConstraints::new(20.0, 3.0, 4.0, 2.0);
+        let qi = State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+        let qf = State{x: 300.0, y: 100.0, z: 20.0, yaw: 1.0, pitch: 0.0};
+        let maneuver = DubinsManeuver3D::try_with_vehicle(qi, qf, &vehicle).unwrap();
+        assert_eq!(maneuver.radii(), vehicle.turn_radii().unwrap());
+    }
+
+    #[test]
+    fn load_factor_must_exceed_one() {
+        let vehicle = VehicleConstraints::new(20.0, 3.0, 4.0, 1.0);
+        assert!(vehicle.turn_radii().is_err());
+    }
+}
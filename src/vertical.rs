@@ -8,11 +8,14 @@ use crate::dubins2d::{
     SegmentType
 };
 
-pub(crate) fn get_vertical(qi: (f64, f64, f64), qf: (f64, f64, f64), rhomin: f64, pitchmax: (f64, f64)) -> DubinsManeuver2D {
+/// Shortest vertical CSC maneuver within the pitch limits, `radii` are the
+/// radii for pulling up and pushing over
+pub(crate) fn get_vertical(qi: (f64, f64, f64), qf: (f64, f64, f64), radii: (f64, f64), pitchmax: (f64, f64)) -> DubinsManeuver2D {
     let mut maneuver = DubinsManeuver2D{
         qi: qi, 
         qf: qf,
-        rhomin: rhomin,
+        rhomin: radii.0.min(radii.1),
+        radii: radii,
         maneuver: DubinsStruct{
             t: 0.0,
             p: 0.0,
@@ -42,13 +45,12 @@ pub(crate) fn get_vertical(qi: (f64, f64, f64), qf: (f64, f64, f64), rhomin: f64
 
     for path in _paths {
         if path.t.abs() < PI && path.q.abs() < PI {
-            let center_angle: f64;
-            if path.case.a == SegmentType::LEFT {
-                center_angle = maneuver.qi.2 + path.t;
+            let center_angle = if path.case.a == SegmentType::LEFT {
+                maneuver.qi.2 + path.t
             }
             else {
-                center_angle = maneuver.qi.2 - path.t;
-            }
+                maneuver.qi.2 - path.t
+            };
             if center_angle < pitchmax.0 || center_angle > pitchmax.1 {
                 continue;
             }
@@ -60,6 +62,31 @@ pub(crate) fn get_vertical(qi: (f64, f64, f64), qf: (f64, f64, f64), rhomin: f64
     return maneuver;
 }
 
+/// Lowest and highest altitude reached by a vertical maneuver
+///
+/// Along an arc the altitude is extremal where the pitch passes through zero
+/// or pi, otherwise the extremes lie at the ends of the segments.
+pub(crate) fn altitude_range(maneuver: &DubinsManeuver2D) -> (f64, f64) {
+    let mut low = maneuver.qi.1.min(maneuver.qf.1);
+    let mut high = maneuver.qi.1.max(maneuver.qf.1);
+    for segment in maneuver.segments() {
+        low = low.min(segment.end.y);
+        high = high.max(segment.end.y);
+        if let Some(center) = segment.center {
+            let sign = if segment.kind == SegmentType::LEFT { 1.0 } else { -1.0 };
+            let swept = segment.length / segment.radius;
+            for pitch in [0.0, PI] {
+                if mod2pi(sign * (pitch - segment.start.yaw)) <= swept {
+                    let z = center.1 - sign * segment.radius * pitch.cos();
+                    low = low.min(z);
+                    high = high.max(z);
+                }
+            }
+        }
+    }
+    return (low, high);
+}
+
 fn _lsl(maneuver: &DubinsManeuver2D) -> DubinsStruct {
     let theta1 = maneuver.qi.2;
     let theta2 = maneuver.qf.2;
@@ -71,7 +98,7 @@ fn _lsl(maneuver: &DubinsManeuver2D) -> DubinsStruct {
         let p1 = (maneuver.qi.0, maneuver.qi.1);
         let p2 = (maneuver.qf.0, maneuver.qf.1);
 
-        let radius = maneuver.rhomin;
+        let radius = maneuver.radius_of(SegmentType::LEFT);
 
         let c1 = radius * theta1.cos();
         let s1 = radius * theta1.sin();
@@ -86,7 +113,7 @@ fn _lsl(maneuver: &DubinsManeuver2D) -> DubinsStruct {
         let center_angle = (diff.1).atan2(diff.0);
 
         t = mod2pi(-theta1 + center_angle);
-        p = center_distance / radius;
+        p = center_distance / maneuver.rhomin;
         q = mod2pi(theta2 - center_angle);
 
         if t > PI {
@@ -95,7 +122,7 @@ fn _lsl(maneuver: &DubinsManeuver2D) -> DubinsStruct {
             let turn_end_y = o2.1 - radius * theta1.cos();
             let diff_y = turn_end_y - p1.1;
             if theta1.abs() > 1e-5 && ((diff_y < 0.0) == (theta1 < 0.0)) {
-                p = diff_y / theta1.sin() / radius;
+                p = diff_y / theta1.sin() / maneuver.rhomin;
             }
             else {
                 t = core::f64::INFINITY;
@@ -109,7 +136,7 @@ fn _lsl(maneuver: &DubinsManeuver2D) -> DubinsStruct {
             let turn_end_y = o1.1 - radius * theta2.cos();
             let diff_y = p2.1 - turn_end_y;
             if theta2.abs() > 1e-5 && ((diff_y < 0.0) == (theta2 < 0.0)) {
-                p = diff_y / theta2.sin() / radius;
+                p = diff_y / theta2.sin() / maneuver.rhomin;
             }
             else {
                 t = core::f64::INFINITY;
@@ -119,7 +146,7 @@ fn _lsl(maneuver: &DubinsManeuver2D) -> DubinsStruct {
         }
     }
 
-    let length = (t+p+q) * maneuver.rhomin;
+    let length = (t+q) * maneuver.radius_of(SegmentType::LEFT) + p * maneuver.rhomin;
     
     let ds = DubinsStruct {
         t: t,
@@ -146,7 +173,7 @@ fn _rsr(maneuver: &DubinsManeuver2D) -> DubinsStruct {
         let p1 = (maneuver.qi.0, maneuver.qi.1);
         let p2 = (maneuver.qf.0, maneuver.qf.1);
 
-        let radius = maneuver.rhomin;
+        let radius = maneuver.radius_of(SegmentType::RIGHT);
 
         let c1 = radius * theta1.cos();
         let s1 = radius * theta1.sin();
@@ -161,7 +188,7 @@ fn _rsr(maneuver: &DubinsManeuver2D) -> DubinsStruct {
         let center_angle = (diff.1).atan2(diff.0);
 
         t = mod2pi(theta1 - center_angle);
-        p = center_distance / radius;
+        p = center_distance / maneuver.rhomin;
         q = mod2pi(-theta2 + center_angle);
 
         if t > PI {
@@ -170,7 +197,7 @@ fn _rsr(maneuver: &DubinsManeuver2D) -> DubinsStruct {
             let turn_end_y = o2.1 + radius * theta1.cos();
             let diff_y = turn_end_y - p1.1;
             if theta1.abs() > 1e-5 && (diff_y < 0.0) == (theta1 < 0.0) {
-                p = diff_y / theta1.sin() / radius;
+                p = diff_y / theta1.sin() / maneuver.rhomin;
             }
             else {
                 t = core::f64::INFINITY;
@@ -184,7 +211,7 @@ fn _rsr(maneuver: &DubinsManeuver2D) -> DubinsStruct {
             let turn_end_y = o1.1 + radius * theta2.cos();
             let diff_y = p2.1 - turn_end_y;
             if theta2.abs() > 1e-5 && (diff_y < 0.0) == (theta2 < 0.0) {
-                p = diff_y / theta2.sin() / radius;
+                p = diff_y / theta2.sin() / maneuver.rhomin;
             }
             else {
                 t = core::f64::INFINITY;
@@ -194,7 +221,7 @@ fn _rsr(maneuver: &DubinsManeuver2D) -> DubinsStruct {
         }
     }
 
-    let length = (t+p+q) * maneuver.rhomin;
+    let length = (t+q) * maneuver.radius_of(SegmentType::RIGHT) + p * maneuver.rhomin;
     
     let ds = DubinsStruct {
         t: t,
@@ -222,12 +249,13 @@ fn _lsr(maneuver: &DubinsManeuver2D, pitchmax: (f64, f64)) -> DubinsStruct {
     let p1 = (maneuver.qi.0, maneuver.qi.1);
     let p2 = (maneuver.qf.0, maneuver.qf.1);
 
-    let radius = maneuver.rhomin;
+    let r1 = maneuver.radius_of(SegmentType::LEFT);
+    let r2 = maneuver.radius_of(SegmentType::RIGHT);
 
-    let c1 = radius * theta1.cos();
-    let s1 = radius * theta1.sin();
-    let c2 = radius * theta2.cos();
-    let s2 = radius * theta2.sin();
+    let c1 = r1 * theta1.cos();
+    let s1 = r1 * theta1.sin();
+    let c2 = r2 * theta2.cos();
+    let s2 = r2 * theta2.sin();
 
     let o1 = (p1.0 - s1, p1.1 + c1);
     let o2 = (p2.0 + s2, p2.1 - c2);
@@ -235,9 +263,9 @@ fn _lsr(maneuver: &DubinsManeuver2D, pitchmax: (f64, f64)) -> DubinsStruct {
     let mut diff = (o2.0 - o1.0, o2.1 - o1.1);
     let center_distance = (diff.0 * diff.0 + diff.1 * diff.1).sqrt();
     
-    let mut alpha = (2.0 * radius / center_distance).asin();
-    if center_distance < 2.0 * radius {
-        diff.0 = (4.0 * radius * radius - diff.1 *diff.1).sqrt();
+    let mut alpha = ((r1 + r2) / center_distance).asin();
+    if center_distance < r1 + r2 {
+        diff.0 = ((r1 + r2) * (r1 + r2) - diff.1 * diff.1).sqrt();
         alpha = PI/2.0;
     }
     
@@ -245,7 +273,7 @@ fn _lsr(maneuver: &DubinsManeuver2D, pitchmax: (f64, f64)) -> DubinsStruct {
 
     if center_angle < pitchmax.1 {
         t = mod2pi(-theta1 + center_angle);
-        p = (center_distance * center_distance - 4.0 * radius * radius).max(0.0).sqrt() / radius;
+        p = (center_distance * center_distance - (r1 + r2) * (r1 + r2)).max(0.0).sqrt() / maneuver.rhomin;
         q = mod2pi(-theta2 + center_angle);
     }
     else {
@@ -253,15 +281,15 @@ fn _lsr(maneuver: &DubinsManeuver2D, pitchmax: (f64, f64)) -> DubinsStruct {
         t = mod2pi(-theta1 + center_angle);
         q = mod2pi(-theta2 + center_angle);
 
-        let c = radius * center_angle.cos();
-        let s = radius * center_angle.sin();
-        let w1 = (o1.0 + s, o1.1 - c);
-        let w2 = (o2.0 - s, o2.1 + c);
+        let c = center_angle.cos();
+        let s = center_angle.sin();
+        let w1 = (o1.0 + r1 * s, o1.1 - r1 * c);
+        let w2 = (o2.0 - r2 * s, o2.1 + r2 * c);
 
-        p = (w2.1 - w1.1) / center_angle.sin() / radius;
+        p = (w2.1 - w1.1) / center_angle.sin() / maneuver.rhomin;
     }
 
-    let length = (t+p+q) * maneuver.rhomin;
+    let length = t * r1 + p * maneuver.rhomin + q * r2;
     
     let ds = DubinsStruct {
         t: t,
@@ -288,12 +316,13 @@ fn _rsl(maneuver: &DubinsManeuver2D, pitchmax: (f64, f64)) -> DubinsStruct {
     let p1 = (maneuver.qi.0, maneuver.qi.1);
     let p2 = (maneuver.qf.0, maneuver.qf.1);
 
-    let radius = maneuver.rhomin;
+    let r1 = maneuver.radius_of(SegmentType::RIGHT);
+    let r2 = maneuver.radius_of(SegmentType::LEFT);
 
-    let c1 = radius * theta1.cos();
-    let s1 = radius * theta1.sin();
-    let c2 = radius * theta2.cos();
-    let s2 = radius * theta2.sin();
+    let c1 = r1 * theta1.cos();
+    let s1 = r1 * theta1.sin();
+    let c2 = r2 * theta2.cos();
+    let s2 = r2 * theta2.sin();
 
     let o1 = (p1.0 + s1, p1.1 - c1);
     let o2 = (p2.0 - s2, p2.1 + c2);
@@ -301,9 +330,9 @@ fn _rsl(maneuver: &DubinsManeuver2D, pitchmax: (f64, f64)) -> DubinsStruct {
     let mut diff = (o2.0 - o1.0, o2.1 - o1.1);
     let center_distance = (diff.0 * diff.0 + diff.1 * diff.1).sqrt();
     
-    let mut alpha = (2.0 * radius / center_distance).asin();
-    if center_distance < 2.0 * radius {
-        diff.0 = (4.0 * radius * radius - diff.1 * diff.1).sqrt();
+    let mut alpha = ((r1 + r2) / center_distance).asin();
+    if center_distance < r1 + r2 {
+        diff.0 = ((r1 + r2) * (r1 + r2) - diff.1 * diff.1).sqrt();
         alpha = PI/2.0;
     }
     
@@ -311,7 +340,7 @@ fn _rsl(maneuver: &DubinsManeuver2D, pitchmax: (f64, f64)) -> DubinsStruct {
 
     if center_angle > pitchmax.0 {
         t = mod2pi(theta1 - center_angle);
-        p = (center_distance * center_distance - 4.0 * radius * radius).max(0.0).sqrt() / radius;
+        p = (center_distance * center_distance - (r1 + r2) * (r1 + r2)).max(0.0).sqrt() / maneuver.rhomin;
         q = mod2pi(theta2 - center_angle);
     }
     else {
@@ -319,15 +348,15 @@ fn _rsl(maneuver: &DubinsManeuver2D, pitchmax: (f64, f64)) -> DubinsStruct {
         t = mod2pi(theta1 - center_angle);
         q = mod2pi(theta2 - center_angle);
 
-        let c = radius * center_angle.cos();
-        let s = radius * center_angle.sin();
-        let w1 = (o1.0 - s, o1.1 + c);
-        let w2 = (o2.0 + s, o2.1 - c);
+        let c = center_angle.cos();
+        let s = center_angle.sin();
+        let w1 = (o1.0 - r1 * s, o1.1 + r1 * c);
+        let w2 = (o2.0 + r2 * s, o2.1 - r2 * c);
 
-        p = (w2.1 - w1.1) / center_angle.sin() / radius;
+        p = (w2.1 - w1.1) / center_angle.sin() / maneuver.rhomin;
     }
 
-    let length = (t+p+q) * maneuver.rhomin;
+    let length = t * r1 + p * maneuver.rhomin + q * r2;
     
     let ds = DubinsStruct {
         t: t,
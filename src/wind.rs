@@ -0,0 +1,492 @@
+use crate::{PI, State};
+use crate::error::{DubinsError, Endpoint};
+use crate::validation::{validate, validate_radius, validate_state2d};
+use crate::dubins2d::{DubinsManeuver2D, ManeuverCase, State2D};
+use crate::dubins2d::SegmentType::{LEFT as L, RIGHT as R, STRAIGHT as S};
+use crate::dubins3d::{DubinsManeuver3D, TurnRadii};
+
+/// Upper limit on the steps taken while searching for the arrival time
+const MAX_ARRIVAL_ITERATIONS: usize = 10000;
+
+/// Horizontal words followed by the arrival search
+const WORDS: [ManeuverCase; 6] = [
+    ManeuverCase{a: L, b: S, c: L},
+    ManeuverCase{a: R, b: S, c: R},
+    ManeuverCase{a: L, b: S, c: R},
+    ManeuverCase{a: R, b: S, c: L},
+    ManeuverCase{a: R, b: L, c: R},
+    ManeuverCase{a: L, b: R, c: L}
+];
+
+/// Gap between flight time and arrival time accepted as arrival, relative to
+/// the arrival time
+const ARRIVAL_TOLERANCE: f64 = 1e-9;
+
+/// Planar Dubins maneuver flown at constant airspeed through a constant wind
+///
+/// The maneuver is planned in the frame moving with the air. There the
+/// vehicle flies an ordinary Dubins maneuver to a virtual target that drifts
+/// against the wind, and over the ground its arcs become trochoids. The yaw
+/// of the states is the heading of the vehicle through the air, the ground
+/// track is crabbed by the wind. The arrival time is the earliest found at
+/// which flying to the virtual target takes exactly as long as the wind
+/// needs to carry it back onto the goal. Every Dubins word is followed on
+/// its own, since a longer word may still arrive where the shortest one
+/// already falls short.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindManeuver2D {
+    air: DubinsManeuver2D,
+    qf: State2D,
+    airspeed: f64,
+    wind: (f64, f64),
+    duration: f64
+}
+
+impl WindManeuver2D {
+    /// Create the minimum-time maneuver to reach `qf` over the ground in a
+    /// constant `wind`, given as velocity of the air over the ground
+    ///
+    /// Panics if no maneuver can be constructed, see `try_new` for a fallible variant.
+    pub fn new(qi: State2D, qf: State2D, rhomin: f64, airspeed: f64, wind: (f64, f64)) -> Self {
+        match WindManeuver2D::try_new(qi, qf, rhomin, airspeed, wind) {
+            Ok(maneuver) => maneuver,
+            Err(err) => panic!("unable to construct 2D wind maneuver: {}", err)
+        }
+    }
+
+    /// Create the minimum-time maneuver to reach `qf` over the ground in a
+    /// constant `wind`, returning an error if no maneuver can be constructed
+    ///
+    /// Fails with `SearchFailed` if no word arrives within the search limits.
+    pub fn try_new(qi: State2D, qf: State2D, rhomin: f64, airspeed: f64, wind: (f64, f64)) -> Result<Self, DubinsError> {
+        validate_radius(rhomin)?;
+        validate_state2d(&qi, Endpoint::Start)?;
+        validate_state2d(&qf, Endpoint::Goal)?;
+        let wind_speed = validate_wind(airspeed, &[wind.0, wind.1])?;
+
+        let start = (qi.x, qi.y, qi.yaw);
+        let target_at = |t: f64| (qf.x - wind.0 * t, qf.y - wind.1 * t, qf.yaw);
+        let word_at = |case: ManeuverCase| move |t: f64| {
+            let air = DubinsManeuver2D::solve_all(start, target_at(t), (rhomin, rhomin), false)
+                .into_iter()
+                .find(|air| air.case() == case);
+            return Ok((air.as_ref().map_or(core::f64::INFINITY, |air| air.length()), air));
+        };
+
+        // LSL and RSR always exist and are at most (4 pi + 2) rhomin longer
+        // than the distance, which grows by at most the wind speed
+        let distance = (qf.x - qi.x).hypot(qf.y - qi.y);
+        let bounds = (
+            distance / (airspeed + wind_speed),
+            (distance + (4.0 * PI + 2.0) * rhomin) / (airspeed - wind_speed)
+        );
+        let (duration, air) = earliest_word_arrival(word_at, airspeed, wind_speed, rhomin / airspeed, bounds)?;
+
+        return Ok(WindManeuver2D{air: air, qf: qf, airspeed: airspeed, wind: wind, duration: duration});
+    }
+
+    /// Maneuver flown relative to the air, from the start to the virtual target
+    pub fn air_maneuver(&self) -> &DubinsManeuver2D {
+        return &self.air;
+    }
+
+    /// Where the goal lies relative to the air at the arrival time
+    pub fn virtual_target(&self) -> State2D {
+        return self.air.goal();
+    }
+
+    /// Initial configuration of the maneuver
+    pub fn start(&self) -> State2D {
+        return self.air.start();
+    }
+
+    /// Final configuration over the ground
+    pub fn goal(&self) -> State2D {
+        return self.qf;
+    }
+
+    /// Speed of the vehicle through the air
+    pub fn airspeed(&self) -> f64 {
+        return self.airspeed;
+    }
+
+    /// Velocity of the air over the ground
+    pub fn wind(&self) -> (f64, f64) {
+        return self.wind;
+    }
+
+    /// Time needed to reach the goal
+    pub fn duration(&self) -> f64 {
+        return self.duration;
+    }
+
+    /// Configuration over the ground at time `t`, clamped to the maneuver
+    ///
+    /// For `t >= duration()` the goal is returned exactly.
+    pub fn state_at_time(&self, t: f64) -> State2D {
+        if t >= self.duration {
+            return self.qf;
+        }
+        let t = t.max(0.0);
+        let q = self.air.state_at(self.airspeed * t);
+        return State2D{x: q.x + self.wind.0 * t, y: q.y + self.wind.1 * t, yaw: q.yaw};
+    }
+
+    /// Velocity over the ground at time `t`, clamped to the maneuver
+    pub fn ground_velocity_at_time(&self, t: f64) -> (f64, f64) {
+        let t = t.max(0.0).min(self.duration);
+        let yaw = self.air.state_at(self.airspeed * t).yaw;
+        return (self.airspeed * yaw.cos() + self.wind.0, self.airspeed * yaw.sin() + self.wind.1);
+    }
+
+    /// Configurations over the ground spaced `dt` apart in time, the last
+    /// item is exactly the goal
+    ///
+    /// Panics if `dt` is not positive.
+    pub fn sample_by_time(&self, dt: f64) -> Vec<State2D> {
+        assert!(dt > 0.0, "sampling interval must be positive, got {}", dt);
+        let count = (self.duration / dt).ceil() as usize;
+        let mut points: Vec<State2D> = (0..count).map(|i| self.state_at_time(dt * i as f64)).collect();
+        points.push(self.qf);
+        return points;
+    }
+}
+
+/// 3D Dubins maneuver flown at constant airspeed through a constant wind
+///
+/// The 3D counterpart of `WindManeuver2D`, yaw and pitch of the states
+/// describe the direction of flight through the air and the pitch limits
+/// apply to it. A vertical wind component lifts or sinks the whole maneuver.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindManeuver3D {
+    air: DubinsManeuver3D,
+    qf: State,
+    airspeed: f64,
+    wind: (f64, f64, f64),
+    duration: f64
+}
+
+impl WindManeuver3D {
+    /// Create the minimum-time maneuver to reach `qf` over the ground in a
+    /// constant `wind`, given as velocity of the air over the ground
+    ///
+    /// Panics if no maneuver can be constructed, see `try_new` for a fallible variant.
+    pub fn new(qi: State, qf: State, rhomin: f64, pitchlims: (f64, f64), airspeed: f64, wind: (f64, f64, f64)) -> Self {
+        match WindManeuver3D::try_new(qi, qf, rhomin, pitchlims, airspeed, wind) {
+            Ok(maneuver) => maneuver,
+            Err(err) => panic!("unable to construct 3D wind maneuver: {}", err)
+        }
+    }
+
+    /// Create the minimum-time maneuver to reach `qf` over the ground in a
+    /// constant `wind`, returning an error if no maneuver can be constructed
+    ///
+    /// As in 2D, fails with `SearchFailed` if no word arrives within the
+    /// search limits.
+    pub fn try_new(qi: State, qf: State, rhomin: f64, pitchlims: (f64, f64), airspeed: f64, wind: (f64, f64, f64)) -> Result<Self, DubinsError> {
+        validate(&qi, &qf, rhomin, pitchlims)?;
+        let wind_speed = validate_wind(airspeed, &[wind.0, wind.1, wind.2])?;
+
+        let target_at = |t: f64| State {
+            x: qf.x - wind.0 * t,
+            y: qf.y - wind.1 * t,
+            z: qf.z - wind.2 * t,
+            yaw: qf.yaw,
+            pitch: qf.pitch
+        };
+        let word_at = |case: ManeuverCase| move |t: f64| {
+            let air = DubinsManeuver3D::solve_lateral(qi, target_at(t), TurnRadii::symmetric(rhomin), pitchlims, None, Some(case)).ok();
+            return Ok((air.as_ref().map_or(core::f64::INFINITY, |air| air.length()), air));
+        };
+
+        let distance = ((qf.x - qi.x).powi(2) + (qf.y - qi.y).powi(2) + (qf.z - qi.z).powi(2)).sqrt();
+        let bounds = (distance / (airspeed + wind_speed), core::f64::INFINITY);
+        let (duration, air) = earliest_word_arrival(word_at, airspeed, wind_speed, rhomin / airspeed, bounds)?;
+
+        return Ok(WindManeuver3D{air: air, qf: qf, airspeed: airspeed, wind: wind, duration: duration});
+    }
+
+    /// Maneuver flown relative to the air, from the start to the virtual target
+    pub fn air_maneuver(&self) -> &DubinsManeuver3D {
+        return &self.air;
+    }
+
+    /// Where the goal lies relative to the air at the arrival time
+    pub fn virtual_target(&self) -> State {
+        return self.air.goal();
+    }
+
+    /// Initial state of the maneuver
+    pub fn start(&self) -> State {
+        return self.air.start();
+    }
+
+    /// Final state over the ground
+    pub fn goal(&self) -> State {
+        return self.qf;
+    }
+
+    /// Speed of the vehicle through the air
+    pub fn airspeed(&self) -> f64 {
+        return self.airspeed;
+    }
+
+    /// Velocity of the air over the ground
+    pub fn wind(&self) -> (f64, f64, f64) {
+        return self.wind;
+    }
+
+    /// Time needed to reach the goal
+    pub fn duration(&self) -> f64 {
+        return self.duration;
+    }
+
+    /// State over the ground at time `t`, clamped to the maneuver
+    ///
+    /// For `t >= duration()` the goal is returned exactly. Panics if the
+    /// maneuver holds no path.
+    pub fn state_at_time(&self, t: f64) -> State {
+        if t >= self.duration {
+            return self.qf;
+        }
+        let t = t.max(0.0);
+        let q = self.air.state_at(self.airspeed * t);
+        return State {
+            x: q.x + self.wind.0 * t,
+            y: q.y + self.wind.1 * t,
+            z: q.z + self.wind.2 * t,
+            yaw: q.yaw,
+            pitch: q.pitch
+        };
+    }
+
+    /// Velocity over the ground at time `t`, clamped to the maneuver
+    ///
+    /// Panics if the maneuver holds no path.
+    pub fn ground_velocity_at_time(&self, t: f64) -> (f64, f64, f64) {
+        let t = t.max(0.0).min(self.duration);
+        let q = self.air.state_at(self.airspeed * t);
+        return (
+            self.airspeed * q.pitch.cos() * q.yaw.cos() + self.wind.0,
+            self.airspeed * q.pitch.cos() * q.yaw.sin() + self.wind.1,
+            self.airspeed * q.pitch.sin() + self.wind.2
+        );
+    }
+
+    /// States over the ground spaced `dt` apart in time, the last item is
+    /// exactly the goal
+    ///
+    /// Panics if `dt` is not positive or the maneuver holds no path.
+    pub fn sample_by_time(&self, dt: f64) -> Vec<State> {
+        assert!(dt > 0.0, "sampling interval must be positive, got {}", dt);
+        let count = (self.duration / dt).ceil() as usize;
+        let mut points: Vec<State> = (0..count).map(|i| self.state_at_time(dt * i as f64)).collect();
+        points.push(self.qf);
+        return points;
+    }
+}
+
+/// Check the airspeed and wind components, returning the wind speed
+fn validate_wind(airspeed: f64, wind: &[f64]) -> Result<f64, DubinsError> {
+    if !(airspeed > 0.0 && airspeed.is_finite()) {
+        return Err(DubinsError::InvalidWind("airspeed must be positive"));
+    }
+    if wind.iter().any(|w| !w.is_finite()) {
+        return Err(DubinsError::InvalidWind("wind must be finite"));
+    }
+    let wind_speed = wind.iter().map(|w| w * w).sum::<f64>().sqrt();
+    if wind_speed >= airspeed {
+        return Err(DubinsError::InvalidWind("wind must be slower than the airspeed"));
+    }
+    return Ok(wind_speed);
+}
+
+/// Earliest arrival over every horizontal word, each followed on its own by
+/// `earliest_arrival`
+///
+/// The shortest maneuver may switch to a word that is already too short
+/// while a longer word still arrives exactly in time, so it can arrive late
+/// or not at all. Words that never arrive would be searched up to the latest
+/// of `bounds`, so the horizon is doubled from `max_step` on until a word
+/// arrives, and once one does, the others are only searched up to its
+/// arrival.
+fn earliest_word_arrival<M, W, F>(word_at: W, airspeed: f64, wind_speed: f64, max_step: f64, bounds: (f64, f64)) -> Result<(f64, M), DubinsError>
+where
+    W: Fn(ManeuverCase) -> F,
+    F: Fn(f64) -> Result<(f64, Option<M>), DubinsError>
+{
+    let mut span = max_step;
+    loop {
+        let horizon = (bounds.0 + span).min(bounds.1);
+        let mut latest = horizon;
+        let mut arrival: Option<(f64, M)> = None;
+        for case in WORDS {
+            if let Ok((t, Some(maneuver))) = earliest_arrival(word_at(case), airspeed, wind_speed, max_step, (bounds.0, latest)) {
+                latest = t;
+                arrival = Some((t, maneuver));
+            }
+        }
+        if let Some(arrival) = arrival {
+            return Ok(arrival);
+        }
+        if horizon >= bounds.1 || span >= MAX_ARRIVAL_ITERATIONS as f64 * max_step {
+            return Err(DubinsError::SearchFailed);
+        }
+        span *= 2.0;
+    }
+}
+
+/// Earliest time `t` at which the air-relative maneuver returned by `solve`
+/// for the virtual target at `t` is flown in exactly `t`
+///
+/// Starting from the earliest of `bounds`, `t` advances by the gap between flight time
+/// and `t` over `1 + wind_speed / airspeed`, the fastest the gap closes while
+/// the maneuver changes continuously, but never by more than `max_step` so
+/// that brief dips of the gap are not stepped over. Where the gap jumps
+/// across zero, as an arc wraps around or the maneuver appears already too
+/// short, nothing arrives there and the search continues up to the latest
+/// of `bounds`. `solve` returns an infinite length where no maneuver exists.
+fn earliest_arrival<M, F>(solve: F, airspeed: f64, wind_speed: f64, max_step: f64, bounds: (f64, f64)) -> Result<(f64, M), DubinsError>
+where F: Fn(f64) -> Result<(f64, M), DubinsError>
+{
+    let closing = 1.0 + wind_speed / airspeed;
+    let gap = |t: f64| solve(t).map(|(length, maneuver)| (length / airspeed - t, maneuver));
+
+    let mut t = bounds.0;
+    let (mut current, mut maneuver) = gap(t)?;
+    for _ in 0..MAX_ARRIVAL_ITERATIONS {
+        if t > bounds.1 {
+            break;
+        }
+        let tolerance = ARRIVAL_TOLERANCE * t.max(1.0);
+        if current.abs() <= tolerance {
+            return Ok((t, maneuver));
+        }
+
+        let next = if current > 0.0 { t + (current / closing).min(max_step) } else { t + max_step };
+        let (next_gap, next_maneuver) = gap(next)?;
+        if current > tolerance && next_gap < -tolerance {
+            let (arrival, arrival_gap, arrival_maneuver) = bisect(gap, t, (next, next_gap, next_maneuver), tolerance)?;
+            if arrival_gap.abs() <= tolerance {
+                return Ok((arrival, arrival_maneuver));
+            }
+            t = arrival;
+            current = arrival_gap;
+            maneuver = arrival_maneuver;
+            continue;
+        }
+        t = next;
+        current = next_gap;
+        maneuver = next_maneuver;
+    }
+    return Err(DubinsError::SearchFailed);
+}
+
+/// Narrow down `low`, where `gap` exceeds `tolerance`, and `high`, where it
+/// does not, until the gap at `high` closes or the interval cannot shrink
+/// any further at a jump
+fn bisect<M, F>(gap: F, mut low: f64, mut high: (f64, f64, M), tolerance: f64) -> Result<(f64, f64, M), DubinsError>
+where F: Fn(f64) -> Result<(f64, M), DubinsError>
+{
+    while high.1.abs() > tolerance {
+        let mid = 0.5 * (low + high.0);
+        if mid <= low || mid >= high.0 {
+            break;
+        }
+        let (mid_gap, mid_maneuver) = gap(mid)?;
+        if mid_gap > tolerance {
+            low = mid;
+        }
+        else {
+            high = (mid, mid_gap, mid_maneuver);
+        }
+    }
+    return Ok(high);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mod_pi;
+
+    const RHOMIN: f64 = 10.0;
+    const AIRSPEED: f64 = 15.0;
+
+    fn assert_arrives_2d(maneuver: &WindManeuver2D) {
+        let goal = maneuver.goal();
+        let duration = maneuver.duration();
+        assert_eq!(maneuver.state_at_time(duration), goal);
+
+        // Just before the arrival the state comes from the air maneuver
+        let end = maneuver.state_at_time(duration * (1.0 - 1e-12));
+        assert!((end.x - goal.x).hypot(end.y - goal.y) < 1e-6);
+        assert!(mod_pi(end.yaw - goal.yaw).abs() < 1e-6);
+
+        let dt = 0.1;
+        let samples = maneuver.sample_by_time(dt);
+        let last = samples[samples.len() - 2];
+        assert_eq!(samples[samples.len() - 1], goal);
+        assert!((last.x - goal.x).hypot(last.y - goal.y) <= (AIRSPEED + 8.0) * dt);
+    }
+
+    fn assert_arrives_3d(maneuver: &WindManeuver3D) {
+        let goal = maneuver.goal();
+        let duration = maneuver.duration();
+        assert_eq!(maneuver.state_at_time(duration), goal);
+
+        let end = maneuver.state_at_time(duration * (1.0 - 1e-12));
+        let distance = ((end.x - goal.x).powi(2) + (end.y - goal.y).powi(2) + (end.z - goal.z).powi(2)).sqrt();
+        assert!(distance < 1e-6);
+        assert!(mod_pi(end.yaw - goal.yaw).abs() < 1e-6 && (end.pitch - goal.pitch).abs() < 1e-6);
+
+        let dt = 0.1;
+        let samples = maneuver.sample_by_time(dt);
+        let last = samples[samples.len() - 2];
+        let step = ((last.x - goal.x).powi(2) + (last.y - goal.y).powi(2) + (last.z - goal.z).powi(2)).sqrt();
+        assert_eq!(samples[samples.len() - 1], goal);
+        assert!(step <= (AIRSPEED + 8.0) * dt);
+    }
+
+    #[test]
+    fn reaches_the_goal_in_head_tail_and_crosswind() {
+        let qi = State2D{x: 0.0, y: 0.0, yaw: 0.0};
+        let qf = State2D{x: 200.0, y: 50.0, yaw: 0.5};
+        let mut durations = Vec::new();
+        for wind in [(-8.0, 0.0), (8.0, 0.0), (0.0, 8.0)] {
+            let maneuver = WindManeuver2D::new(qi, qf, RHOMIN, AIRSPEED, wind);
+            assert_arrives_2d(&maneuver);
+            durations.push(maneuver.duration());
+        }
+        assert!(durations[1] < durations[2] && durations[2] < durations[0]);
+    }
+
+    #[test]
+    fn reaches_the_goal_in_head_tail_and_crosswind_3d() {
+        let qi = State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+        let qf = State{x: 200.0, y: 50.0, z: 20.0, yaw: 0.5, pitch: 0.0};
+        for wind in [(-8.0, 0.0, 0.0), (8.0, 0.0, 0.0), (0.0, 8.0, 0.0), (0.0, 6.0, -1.0)] {
+            assert_arrives_3d(&WindManeuver3D::new(qi, qf, RHOMIN, (-0.3, 0.3), AIRSPEED, wind));
+        }
+    }
+
+    /// The virtual target passes the start from behind, where the shortest
+    /// word jumps to the straight line that is already too short, so only a
+    /// longer word arrives
+    #[test]
+    fn close_goal_arrives_on_a_longer_word() {
+        let qi = State2D{x: 0.0, y: 0.0, yaw: 0.0};
+        let qf = State2D{x: -10.0, y: 0.0, yaw: 0.0};
+        let maneuver = WindManeuver2D::new(qi, qf, RHOMIN, AIRSPEED, (-8.0, 0.0));
+        assert_arrives_2d(&maneuver);
+
+        let shortest = DubinsManeuver2D::new(qi, maneuver.virtual_target(), RHOMIN);
+        assert!(shortest.length() < maneuver.air_maneuver().length() - 1.0);
+        assert!((maneuver.air_maneuver().length() - AIRSPEED * maneuver.duration()).abs() < 1e-6);
+
+        let qi = State{x: 0.0, y: 0.0, z: 0.0, yaw: 0.0, pitch: 0.0};
+        let qf = State{x: -10.0, y: 0.0, z: 5.0, yaw: 0.0, pitch: 0.0};
+        assert_arrives_3d(&WindManeuver3D::new(qi, qf, RHOMIN, (-0.3, 0.3), AIRSPEED, (-8.0, 0.0, 0.0)));
+    }
+}